@@ -44,6 +44,11 @@
 //! - [X] [`Lock-Token`](crate::headers::LockToken)
 //! - [X] [`Overwrite`](crate::headers::Overwrite)
 //! - [X] [`Timeout`](crate::headers::Timeout)
+//! - [X] [`Prefer`](crate::headers::Prefer) and
+//!   [`Preference-Applied`](crate::headers::PreferenceApplied)
+//!   ([RFC 7240](https://www.rfc-editor.org/rfc/rfc7240),
+//!   [RFC 8144](https://www.rfc-editor.org/rfc/rfc8144))
+//! - [X] [`Brief`](crate::headers::Brief)
 //!
 //! </details>
 //! <details>
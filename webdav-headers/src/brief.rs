@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::BRIEF;
+
+/// The legacy `Brief` header, which is superseded by
+/// [`Prefer: return=minimal`](crate::Preference::ReturnMinimal) as described
+/// in [RFC 8144](https://www.rfc-editor.org/rfc/rfc8144#appendix-B).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Brief {
+    #[default]
+    F,
+    T,
+}
+
+impl headers::Header for Brief {
+    fn name() -> &'static http::HeaderName {
+        &BRIEF
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i http::HeaderValue>,
+    {
+        values
+            .next()
+            .map(headers::HeaderValue::as_bytes)
+            .and_then(|s| match s {
+                b"F" | b"f" => Some(Brief::F),
+                b"T" | b"t" => Some(Brief::T),
+                _ => None,
+            })
+            .ok_or_else(headers::Error::invalid)
+    }
+
+    fn encode<E: Extend<http::HeaderValue>>(&self, values: &mut E) {
+        values.extend(std::iter::once(match self {
+            Brief::F => headers::HeaderValue::from_static("f"),
+            Brief::T => headers::HeaderValue::from_static("t"),
+        }))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    use crate::test::{test_all, test_decode};
+
+    test_all([("f", Brief::F), ("t", Brief::T)]);
+    assert_eq!(test_decode::<Brief>(&["T"]), Some(Brief::T));
+}
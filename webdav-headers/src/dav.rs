@@ -6,7 +6,10 @@ use std::{fmt::Display, str::FromStr};
 
 use itertools::Itertools;
 
-use crate::{utils::HeaderIteratorExt, CodedUrl, ParseString, DAV};
+use crate::{
+    utils::{is_tchar, HeaderIteratorExt},
+    CodedUrl, ParseString, DAV,
+};
 
 pub use self::error::InvalidComplianceClass;
 
@@ -39,15 +42,102 @@ impl headers::Header for Dav {
     }
 }
 
+impl Dav {
+    /// Whether the server supports a compliance class.
+    ///
+    /// Classes other than Coded-URLs are compared case-insensitively, and a
+    /// registered class matches its [`ComplianceClass::Tokens`] form.
+    ///
+    /// ```
+    /// use headers::HeaderMapExt;
+    /// use webdav_headers::{ComplianceClass, Dav};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.insert("dav", "1, 2, 3, access-control, calendar-access".parse()?);
+    ///
+    /// let dav = headers.typed_get::<Dav>().unwrap();
+    /// assert!(dav.supports_locking());
+    /// assert!(dav.supports(ComplianceClass::CalendarAccess));
+    /// assert!(!dav.supports(ComplianceClass::Addressbook));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn supports(&self, class: ComplianceClass) -> bool {
+        self.0.iter().any(|supported| match (supported, &class) {
+            (ComplianceClass::CodedUrl(_), _) | (_, ComplianceClass::CodedUrl(_)) => {
+                *supported == class
+            }
+            // a registered class may have been constructed from its token, and
+            // tokens are compared case-insensitively on both sides
+            (supported, class) => supported
+                .to_string()
+                .eq_ignore_ascii_case(&class.to_string()),
+        })
+    }
+    /// Whether the server supports locking, i.e. compliance class 2.
+    pub fn supports_locking(&self) -> bool {
+        self.supports(ComplianceClass::Two)
+    }
+}
+
 /// Compliance class identifiers used in the `DAV` header.
+///
+/// Besides the classes of [RFC 4918](http://webdav.org/specs/rfc4918.html#dav.compliance.classes),
+/// this includes the classes of common extensions that are registered with
+/// IANA or widely used.
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum ComplianceClass {
     One,
     Two,
     Three,
+    /// `access-control` as defined in [RFC 3744](https://www.rfc-editor.org/rfc/rfc3744#section-7.2)
+    AccessControl,
+    /// `addressbook` as defined in [RFC 6352](https://www.rfc-editor.org/rfc/rfc6352#section-6.1)
+    Addressbook,
+    /// `bind` as defined in [RFC 5842](https://www.rfc-editor.org/rfc/rfc5842#section-8.1)
+    Bind,
+    /// `calendar-access` as defined in [RFC 4791](https://www.rfc-editor.org/rfc/rfc4791#section-5.1)
+    CalendarAccess,
+    /// `calendar-auto-schedule` as defined in [RFC 6638](https://www.rfc-editor.org/rfc/rfc6638#section-2)
+    CalendarAutoSchedule,
+    /// `calendar-schedule`, used by CalDAV servers before RFC 6638
+    CalendarSchedule,
+    /// `extended-mkcol` as defined in [RFC 5689](https://www.rfc-editor.org/rfc/rfc5689#section-3.1)
+    ExtendedMkcol,
+    /// `ordered-collections` as defined in [RFC 3648](https://www.rfc-editor.org/rfc/rfc3648#section-9.1)
+    OrderedCollections,
+    /// `quota`, used by servers that support the quota properties of
+    /// [RFC 4331](https://www.rfc-editor.org/rfc/rfc4331)
+    Quota,
+    /// `redirectrefs` as defined in [RFC 4437](https://www.rfc-editor.org/rfc/rfc4437#section-13)
+    RedirectRefs,
+    /// `sync-collection`, used by servers that support the `sync-collection`
+    /// report of [RFC 6578](https://www.rfc-editor.org/rfc/rfc6578)
+    SyncCollection,
+    /// `version-control` as defined in [RFC 3253](https://www.rfc-editor.org/rfc/rfc3253#section-3.6)
+    VersionControl,
     CodedUrl(Box<CodedUrl>),
     Tokens(Tokens),
 }
+
+impl ComplianceClass {
+    /// The registered tokens and the classes they are parsed as.
+    const TOKENS: [(&'static str, Self); 12] = [
+        ("access-control", Self::AccessControl),
+        ("addressbook", Self::Addressbook),
+        ("bind", Self::Bind),
+        ("calendar-access", Self::CalendarAccess),
+        ("calendar-auto-schedule", Self::CalendarAutoSchedule),
+        ("calendar-schedule", Self::CalendarSchedule),
+        ("extended-mkcol", Self::ExtendedMkcol),
+        ("ordered-collections", Self::OrderedCollections),
+        ("quota", Self::Quota),
+        ("redirectrefs", Self::RedirectRefs),
+        ("sync-collection", Self::SyncCollection),
+        ("version-control", Self::VersionControl),
+    ];
+}
+
 impl Display for ComplianceClass {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -56,6 +146,13 @@ impl Display for ComplianceClass {
             Self::Three => f.write_str("3"),
             Self::CodedUrl(uri) => uri.fmt(f),
             Self::Tokens(s) => s.fmt(f),
+            class => f.write_str(
+                Self::TOKENS
+                    .iter()
+                    .find(|(_, c)| c == class)
+                    .map(|(token, _)| *token)
+                    .expect("all registered classes have a token"),
+            ),
         }
     }
 }
@@ -70,7 +167,13 @@ impl FromStr for ComplianceClass {
             "3" => Ok(Self::Three),
             _ => match CodedUrl::peek(s) {
                 Ok((coded_url, _)) => Ok(Self::CodedUrl(Box::new(coded_url))),
-                Err(_) => Ok(Self::Tokens(s.parse()?)),
+                Err(_) => match Self::TOKENS
+                    .into_iter()
+                    .find(|(token, _)| token.eq_ignore_ascii_case(s))
+                {
+                    Some((_, class)) => Ok(class),
+                    None => Ok(Self::Tokens(s.parse()?)),
+                },
             },
         }
     }
@@ -108,12 +211,7 @@ impl FromStr for Tokens {
     type Err = InvalidComplianceClass;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.chars().find(|c| {
-            !c.is_ascii()
-                || c.is_ascii_control()
-                || c.is_ascii_punctuation()
-                || c.is_ascii_whitespace()
-        }) {
+        match s.chars().find(|&c| !is_tchar(c)) {
             Some(c) => Err(InvalidComplianceClass::InvalidChar(c)),
             None => Ok(Self(s.to_owned())),
         }
@@ -163,5 +261,60 @@ fn test() {
             ))),
             ComplianceClass::Tokens("foobar".parse().unwrap()),
         ]),
-    )
+    );
+
+    let classes = [
+        ComplianceClass::AccessControl,
+        ComplianceClass::Addressbook,
+        ComplianceClass::Bind,
+        ComplianceClass::CalendarAccess,
+        ComplianceClass::CalendarAutoSchedule,
+        ComplianceClass::CalendarSchedule,
+        ComplianceClass::ExtendedMkcol,
+        ComplianceClass::OrderedCollections,
+        ComplianceClass::Quota,
+        ComplianceClass::RedirectRefs,
+        ComplianceClass::SyncCollection,
+        ComplianceClass::VersionControl,
+    ];
+    test(
+        "access-control,addressbook,bind,calendar-access,calendar-auto-schedule,calendar-schedule,\
+         extended-mkcol,ordered-collections,quota,redirectrefs,sync-collection,version-control",
+        Dav(classes.to_vec()),
+    );
+    assert_eq!(
+        "Calendar-Access".parse::<ComplianceClass>().unwrap(),
+        ComplianceClass::CalendarAccess
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn supports() {
+    use crate::test::test_decode;
+
+    let dav =
+        test_decode::<Dav>(&["1, 2, extended-mkcol, <https://example.com/foo>, foo"]).unwrap();
+    assert!(dav.supports_locking());
+    assert!(dav.supports(ComplianceClass::One));
+    assert!(!dav.supports(ComplianceClass::Three));
+    assert!(dav.supports(ComplianceClass::ExtendedMkcol));
+    assert!(dav.supports(ComplianceClass::Tokens("extended-mkcol".parse().unwrap())));
+    assert!(dav.supports(ComplianceClass::Tokens("foo".parse().unwrap())));
+    assert!(dav.supports("<https://example.com/foo>".parse().unwrap()));
+    assert!(!dav.supports(ComplianceClass::Bind));
+
+    // tokens are case-insensitive
+    let dav = test_decode::<Dav>(&["1, Foo, Extended-MKCOL"]).unwrap();
+    assert!(dav.supports(ComplianceClass::Tokens("foo".parse().unwrap())));
+    assert!(dav.supports(ComplianceClass::Tokens("FOO".parse().unwrap())));
+    assert!(dav.supports(ComplianceClass::ExtendedMkcol));
+    assert!(dav.supports(ComplianceClass::Tokens("extended-mkcol".parse().unwrap())));
+    let dav = Dav(vec![ComplianceClass::Tokens(
+        "Calendar-Access".parse().unwrap(),
+    )]);
+    assert!(dav.supports(ComplianceClass::CalendarAccess));
+
+    let dav = test_decode::<Dav>(&["1"]).unwrap();
+    assert!(!dav.supports_locking());
 }
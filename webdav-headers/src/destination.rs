@@ -4,6 +4,8 @@
 
 use crate::{utils::HeaderIteratorExt, DESTINATION};
 
+pub use self::error::ResolveDestinationError;
+
 /// The `Destination` header as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#HEADER_Destination).
 #[derive(Clone, Debug, PartialEq)]
 pub struct Destination(pub http::Uri);
@@ -29,6 +31,187 @@ impl headers::Header for Destination {
     }
 }
 
+impl Destination {
+    /// Resolve the destination of a `COPY` or `MOVE` request to a path
+    /// within the mount point of the server.
+    ///
+    /// The destination may be an absolute URI or an absolute path. The
+    /// scheme and authority of an absolute URI must match `request_uri`, so
+    /// `request_uri` should be reconstructed from the `Host` header if the
+    /// request only contains a path. The path is percent-decoded and `.` and
+    /// `..` segments are removed. A query is ignored. Segments containing
+    /// `/`, `\` or control characters after decoding are rejected.
+    ///
+    /// Returns the path relative to `mount_prefix`, starting with `/` and
+    /// ending with `/` if the destination refers to a collection or the mount
+    /// point itself.
+    ///
+    /// ```
+    /// use webdav_headers::{Destination, ResolveDestinationError};
+    ///
+    /// let request_uri = "https://example.com/dav/a.txt".parse()?;
+    ///
+    /// let destination = Destination("https://example.com/dav/dir/../b%20c.txt".parse()?);
+    /// assert_eq!(destination.resolve(&request_uri, "/dav")?, "/b c.txt");
+    ///
+    /// let destination = Destination("/dav/../etc/passwd".parse()?);
+    /// assert!(matches!(
+    ///     destination.resolve(&request_uri, "/dav"),
+    ///     Err(ResolveDestinationError::OutsideMount)
+    /// ));
+    ///
+    /// let destination = Destination("https://example.org/dav/b.txt".parse()?);
+    /// let error = destination.resolve(&request_uri, "/dav").unwrap_err();
+    /// assert_eq!(error.status_code(), http::StatusCode::BAD_GATEWAY);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn resolve(
+        &self,
+        request_uri: &http::Uri,
+        mount_prefix: &str,
+    ) -> Result<String, ResolveDestinationError> {
+        let destination = &self.0;
+        match (destination.scheme(), destination.authority()) {
+            (Some(_), Some(_)) => {
+                if origin(destination) != origin(request_uri) {
+                    return Err(ResolveDestinationError::OtherServer);
+                }
+            }
+            (None, None) => {}
+            _ => return Err(ResolveDestinationError::InvalidPath),
+        }
+
+        let path = destination.path();
+        if !path.starts_with('/') {
+            return Err(ResolveDestinationError::InvalidPath);
+        }
+
+        let mut segments = Vec::new();
+        for segment in path.split('/') {
+            let segment = percent_decode(segment)?;
+            match &*segment {
+                "" | "." => {}
+                ".." => {
+                    segments
+                        .pop()
+                        .ok_or(ResolveDestinationError::OutsideMount)?;
+                }
+                _ => segments.push(segment),
+            }
+        }
+        // `/a/`, `/a/.` and `/a/b/..` refer to the collection `/a/`
+        let is_collection = matches!(
+            path.rsplit('/')
+                .next()
+                .map(percent_decode)
+                .transpose()?
+                .as_deref(),
+            Some("" | "." | "..")
+        );
+
+        let mut segments = segments.into_iter();
+        for expected in mount_prefix
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+        {
+            match segments.next() {
+                Some(segment) if segment == expected => {}
+                _ => return Err(ResolveDestinationError::OutsideMount),
+            }
+        }
+
+        let mut resolved = String::new();
+        for segment in segments {
+            resolved.push('/');
+            resolved.push_str(&segment);
+        }
+        if resolved.is_empty() || is_collection {
+            resolved.push('/');
+        }
+        Ok(resolved)
+    }
+}
+
+/// The scheme, host and port of an absolute URI.
+fn origin(uri: &http::Uri) -> Option<(String, String, u16)> {
+    let scheme = uri.scheme_str()?.to_ascii_lowercase();
+    let authority = uri.authority()?;
+    let port = match (authority.port_u16(), &*scheme) {
+        (Some(port), _) => port,
+        (None, "http") => 80,
+        (None, "https") => 443,
+        (None, _) => 0,
+    };
+    Some((scheme, authority.host().to_ascii_lowercase(), port))
+}
+
+fn percent_decode(segment: &str) -> Result<String, ResolveDestinationError> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut iter = segment.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let [Some(high), Some(low)] = hex else {
+                    return Err(ResolveDestinationError::InvalidPath);
+                };
+                let byte = std::str::from_utf8(&[high, low])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or(ResolveDestinationError::InvalidPath)?;
+                bytes.push(byte);
+            }
+            b => bytes.push(b),
+        }
+    }
+    match String::from_utf8(bytes) {
+        // an encoded `/` would change the segments of the path, and `\` is a
+        // separator on Windows
+        Ok(segment) if !segment.contains(['/', '\\']) && !segment.contains(char::is_control) => {
+            Ok(segment)
+        }
+        _ => Err(ResolveDestinationError::InvalidPath),
+    }
+}
+
+mod error {
+    /// Error returned by [`Destination::resolve`](super::Destination::resolve).
+    #[derive(Debug)]
+    pub enum ResolveDestinationError {
+        /// The destination isn't a valid path, e.g. because of invalid
+        /// percent-encoding.
+        InvalidPath,
+        /// The destination is outside of the mount point.
+        OutsideMount,
+        /// The destination is on another server.
+        OtherServer,
+    }
+
+    impl ResolveDestinationError {
+        /// The status code of the response to a request with this
+        /// destination.
+        pub fn status_code(&self) -> http::StatusCode {
+            match self {
+                Self::InvalidPath => http::StatusCode::BAD_REQUEST,
+                Self::OutsideMount => http::StatusCode::FORBIDDEN,
+                Self::OtherServer => http::StatusCode::BAD_GATEWAY,
+            }
+        }
+    }
+
+    impl std::fmt::Display for ResolveDestinationError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::InvalidPath => f.write_str("invalid destination path"),
+                Self::OutsideMount => f.write_str("destination is outside of the mount point"),
+                Self::OtherServer => f.write_str("destination is on another server"),
+            }
+        }
+    }
+
+    impl std::error::Error for ResolveDestinationError {}
+}
+
 #[cfg(test)]
 #[test]
 fn test() {
@@ -42,3 +225,101 @@ fn test() {
         ("/foo/bar/123", Destination("/foo/bar/123".parse().unwrap())),
     ])
 }
+
+#[cfg(test)]
+#[test]
+fn resolve() {
+    use pretty_assertions::assert_eq;
+
+    #[track_caller]
+    fn resolve(
+        request_uri: &str,
+        destination: &str,
+        mount_prefix: &str,
+    ) -> Result<String, http::StatusCode> {
+        Destination(destination.parse().unwrap())
+            .resolve(&request_uri.parse().unwrap(), mount_prefix)
+            .map_err(|e| e.status_code())
+    }
+
+    let uri = "http://example.com/dav/a";
+    for (destination, mount_prefix, expected) in [
+        ("/dav/b", "/dav", "/b"),
+        ("/dav/b", "/dav/", "/b"),
+        ("/dav/b/", "/dav", "/b/"),
+        ("/dav", "/dav", "/"),
+        ("/dav/", "/dav", "/"),
+        ("/dav/b", "/", "/dav/b"),
+        ("/dav/b", "", "/dav/b"),
+        ("http://example.com/dav/b", "/dav", "/b"),
+        ("HTTP://EXAMPLE.COM:80/dav/b", "/dav", "/b"),
+        ("/dav/./c/../b", "/dav", "/b"),
+        ("/dav/c/..", "/dav", "/"),
+        ("/dav/c/%2e%2E/b", "/dav", "/b"),
+        ("/dav//b", "/dav", "/b"),
+        ("/dav/b%20c%C3%A4", "/dav", "/b cä"),
+        ("/dav/b?query", "/dav", "/b"),
+    ] {
+        assert_eq!(
+            resolve(uri, destination, mount_prefix),
+            Ok(expected.to_owned()),
+            "{destination}"
+        );
+    }
+
+    for (request_uri, destination, mount_prefix, expected) in [
+        // other servers
+        (
+            uri,
+            "https://example.com/dav/b",
+            "/dav",
+            http::StatusCode::BAD_GATEWAY,
+        ),
+        (
+            uri,
+            "http://example.com:8080/dav/b",
+            "/dav",
+            http::StatusCode::BAD_GATEWAY,
+        ),
+        (
+            uri,
+            "http://example.org/dav/b",
+            "/dav",
+            http::StatusCode::BAD_GATEWAY,
+        ),
+        (
+            "/dav/a",
+            "http://example.com/dav/b",
+            "/dav",
+            http::StatusCode::BAD_GATEWAY,
+        ),
+        // outside of the mount point
+        (uri, "/dav/../b", "/dav", http::StatusCode::FORBIDDEN),
+        (uri, "/davb", "/dav", http::StatusCode::FORBIDDEN),
+        (uri, "/../dav/b", "/", http::StatusCode::FORBIDDEN),
+        (uri, "/dav/%2E%2E/b", "/dav", http::StatusCode::FORBIDDEN),
+        // invalid paths
+        (uri, "/dav/a%2Fb", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%00", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%0Ab", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%7F", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%5Cb", "/dav", http::StatusCode::BAD_REQUEST),
+        (
+            uri,
+            "/dav/..%5C..%5Cetc",
+            "/dav",
+            http::StatusCode::BAD_REQUEST,
+        ),
+        (uri, r"/dav/a\b", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%ff", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%2", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "/dav/a%zz", "/dav", http::StatusCode::BAD_REQUEST),
+        (uri, "example.com:80", "/dav", http::StatusCode::BAD_REQUEST),
+    ] {
+        assert_eq!(
+            resolve(request_uri, destination, mount_prefix),
+            Err(expected),
+            "{destination}"
+        );
+    }
+}
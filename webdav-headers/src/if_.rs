@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::{fmt::Display, str::FromStr};
+use std::{collections::HashSet, fmt::Display, str::FromStr};
 
 use nonempty::NonEmpty;
 
@@ -64,7 +64,10 @@ impl Display for If {
         match self {
             If::NoTagList(lists) => fmt_condition_lists(f, lists)?,
             If::TaggedList(resources) => {
-                for (resource_tag, lists) in &**resources {
+                for (i, (resource_tag, lists)) in resources.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
                     resource_tag.fmt(f)?;
                     f.write_str(" ")?;
                     fmt_condition_lists(f, lists)?
@@ -248,6 +251,284 @@ impl ParseString for ResourceTag {
     }
 }
 
+impl If {
+    /// Build an `If` header, e.g. for a client request.
+    ///
+    /// ```
+    /// use webdav_headers::{CodedUrl, If};
+    ///
+    /// let token: CodedUrl = "<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>".parse()?;
+    /// let if_ = If::builder()
+    ///     .resource("/a")
+    ///     .lock_token(token)
+    ///     .etag(r#"W/"A weak ETag""#)
+    ///     .or()
+    ///     .not_etag(r#""strong ETag""#)
+    ///     .build()?;
+    /// assert_eq!(
+    ///     if_.to_string(),
+    ///     r#"</a> (<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2> [W/"A weak ETag"]) (Not ["strong ETag"])"#
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn builder() -> IfBuilder {
+        IfBuilder::default()
+    }
+    /// Evaluate the header as described in
+    /// [RFC 4918](http://webdav.org/specs/rfc4918.html#if.header.evaluation).
+    ///
+    /// Untagged lists apply to `request_uri`. The header matches if any of
+    /// the lists matches, which is the case if all of its conditions match
+    /// the current state of the resource according to `state`. State tokens
+    /// and entity tags never match a resource that doesn't exist. Entity tags
+    /// are compared with the [weak comparison function](https://www.rfc-editor.org/rfc/rfc9110#section-8.8.3.2),
+    /// so `W/"1"` matches `"1"`.
+    ///
+    /// All state tokens of conditions without `Not` are
+    /// [submitted](IfEvaluation::submitted), even if their list doesn't
+    /// match. A lock only permits a request if its token was submitted and
+    /// the header matches.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use webdav_headers::{CodedUrl, If, ResourceState};
+    ///
+    /// struct Resources(HashMap<String, (String, Vec<CodedUrl>)>);
+    ///
+    /// impl ResourceState for Resources {
+    ///     fn etag(&self, uri: &http::Uri) -> Option<String> {
+    ///         self.0.get(uri.path()).map(|(etag, _)| etag.clone())
+    ///     }
+    ///     fn lock_tokens(&self, uri: &http::Uri) -> Vec<CodedUrl> {
+    ///         self.0
+    ///             .get(uri.path())
+    ///             .map(|(_, tokens)| tokens.clone())
+    ///             .unwrap_or_default()
+    ///     }
+    /// }
+    ///
+    /// let token: CodedUrl = "<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>".parse()?;
+    /// let resources = Resources(HashMap::from([(
+    ///     "/file".to_owned(),
+    ///     (r#""etag""#.to_owned(), vec![token.clone()]),
+    /// )]));
+    ///
+    /// let if_: If = r#"(<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2> ["etag"])"#.parse()?;
+    /// let evaluation = if_.evaluate(&"/file".parse()?, &resources);
+    /// assert!(evaluation.matches);
+    /// assert!(evaluation.is_submitted(&token));
+    ///
+    /// let evaluation = if_.evaluate(&"/other".parse()?, &resources);
+    /// assert!(!evaluation.matches);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn evaluate(&self, request_uri: &http::Uri, state: &impl ResourceState) -> IfEvaluation {
+        fn evaluate_lists(
+            uri: &http::Uri,
+            lists: &NonEmpty<NonEmpty<Condition>>,
+            state: &impl ResourceState,
+        ) -> bool {
+            let etag = state.etag(uri);
+            let lock_tokens = state.lock_tokens(uri);
+            lists.iter().any(|conditions| {
+                conditions.iter().all(|condition| match condition {
+                    Condition::StateToken { not, coded_url } => {
+                        lock_tokens.contains(coded_url) != *not
+                    }
+                    Condition::ETag {
+                        not,
+                        etag: expected,
+                    } => etag.as_ref().is_some_and(|etag| weak_eq(etag, expected)) != *not,
+                })
+            })
+        }
+
+        let (matches, lists) = match self {
+            If::NoTagList(lists) => (evaluate_lists(request_uri, lists, state), vec![&**lists]),
+            If::TaggedList(resources) => (
+                resources
+                    .iter()
+                    .any(|(ResourceTag(uri), lists)| evaluate_lists(uri, lists, state)),
+                resources.iter().map(|(_, lists)| lists).collect(),
+            ),
+        };
+        let submitted = lists
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|condition| match condition {
+                Condition::StateToken {
+                    not: false,
+                    coded_url,
+                } => Some(coded_url.clone()),
+                _ => None,
+            })
+            .collect();
+
+        IfEvaluation { matches, submitted }
+    }
+}
+
+/// Builder for the [`If`] header, created by [`If::builder`].
+///
+/// Conditions are added to the current list, which is ended by
+/// [`or`](Self::or). Lists that are added before the first call to
+/// [`resource`](Self::resource) are untagged and apply to the request URI,
+/// so they can't be combined with tagged lists.
+#[derive(Debug, Default)]
+pub struct IfBuilder {
+    resources: Vec<(Option<ResourceTag>, Vec<NonEmpty<Condition>>)>,
+    conditions: Vec<Condition>,
+    error: Option<InvalidIf>,
+}
+
+impl IfBuilder {
+    /// Start the lists that apply to the resource at `uri`.
+    pub fn resource(mut self, uri: &str) -> Self {
+        self.end_list();
+        if self.resources.iter().any(|(tag, _)| tag.is_none()) {
+            return self.fail(InvalidIf::MixedLists);
+        }
+        match http::Uri::from_str(uri) {
+            Ok(uri) => self.resources.push((Some(ResourceTag(uri)), Vec::new())),
+            Err(e) => return self.fail(InvalidIf::Uri(e)),
+        }
+        self
+    }
+    /// End the current list and start another one for the same resource.
+    pub fn or(mut self) -> Self {
+        if self.conditions.is_empty() {
+            return self.fail(InvalidIf::EmptyConditionList);
+        }
+        self.end_list();
+        self
+    }
+    /// Add a condition to the current list.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        if self.resources.is_empty() {
+            self.resources.push((None, Vec::new()));
+        }
+        self.conditions.push(condition);
+        self
+    }
+    /// Require the resource to be locked with `lock_token`, which also
+    /// submits the lock token.
+    pub fn lock_token(self, lock_token: CodedUrl) -> Self {
+        self.condition(Condition::StateToken {
+            not: false,
+            coded_url: lock_token,
+        })
+    }
+    /// Require the resource not to be locked with `lock_token`.
+    pub fn not_lock_token(self, lock_token: CodedUrl) -> Self {
+        self.condition(Condition::StateToken {
+            not: true,
+            coded_url: lock_token,
+        })
+    }
+    /// Require the resource to have the entity tag `etag`, including its
+    /// quotes.
+    pub fn etag(self, etag: impl Into<String>) -> Self {
+        self.condition(Condition::ETag {
+            not: false,
+            etag: etag.into(),
+        })
+    }
+    /// Require the resource not to have the entity tag `etag`, including its
+    /// quotes.
+    pub fn not_etag(self, etag: impl Into<String>) -> Self {
+        self.condition(Condition::ETag {
+            not: true,
+            etag: etag.into(),
+        })
+    }
+    /// Build the header.
+    ///
+    /// Fails if a list or resource doesn't contain any conditions, a
+    /// resource URI is invalid or tagged and untagged lists are mixed.
+    pub fn build(mut self) -> Result<If, InvalidIf> {
+        self.end_list();
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let resources = NonEmpty::try_collect(self.resources.into_iter().map(|(tag, lists)| {
+            Ok((
+                tag,
+                NonEmpty::from_vec(lists).ok_or(InvalidIf::EmptyConditionList)?,
+            ))
+        }))?
+        .ok_or(InvalidIf::EmptyConditionList)?;
+
+        match resources {
+            NonEmpty {
+                head: (None, lists),
+                ..
+            } => Ok(If::NoTagList(Box::new(lists))),
+            resources => Ok(If::TaggedList(Box::new(resources.map(|(tag, lists)| {
+                (
+                    tag.expect("tagged lists are not mixed with untagged ones"),
+                    lists,
+                )
+            })))),
+        }
+    }
+    fn end_list(&mut self) {
+        if let Some(conditions) = NonEmpty::from_vec(std::mem::take(&mut self.conditions)) {
+            self.resources
+                .last_mut()
+                .expect("conditions are added to a resource")
+                .1
+                .push(conditions);
+        }
+    }
+    fn fail(mut self, error: InvalidIf) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+}
+
+/// Compare entity tags regardless of whether they are weak.
+fn weak_eq(a: &str, b: &str) -> bool {
+    fn opaque_tag(etag: &str) -> &str {
+        etag.strip_prefix("W/").unwrap_or(etag)
+    }
+    opaque_tag(a) == opaque_tag(b)
+}
+
+/// The current state of resources, used by [`If::evaluate`].
+pub trait ResourceState {
+    /// The entity tag of a resource, including quotes and the `W/` prefix of
+    /// weak entity tags, or `None` if the resource doesn't exist.
+    ///
+    /// `uri` is either the request URI or the URI of a resource tag, which
+    /// may be absolute.
+    fn etag(&self, uri: &http::Uri) -> Option<String>;
+    /// The tokens of all locks on a resource, including locks with depth
+    /// infinity on its ancestors.
+    ///
+    /// `uri` is either the request URI or the URI of a resource tag, which
+    /// may be absolute.
+    fn lock_tokens(&self, uri: &http::Uri) -> Vec<CodedUrl>;
+}
+
+/// The result of [`If::evaluate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfEvaluation {
+    /// Whether the header matches. Otherwise, the request must fail with
+    /// `412 Precondition Failed`.
+    pub matches: bool,
+    /// The lock tokens that were submitted with the header.
+    pub submitted: HashSet<CodedUrl>,
+}
+
+impl IfEvaluation {
+    /// Whether the token of a lock was submitted.
+    pub fn is_submitted(&self, lock_token: &CodedUrl) -> bool {
+        self.submitted.contains(lock_token)
+    }
+}
+
 mod error {
     use crate::InvalidCodedUrl;
 
@@ -257,6 +538,7 @@ mod error {
         ExpectedChar(char),
         EmptyConditionList,
         EmptyResourceList,
+        MixedLists,
         CodedUrl(InvalidCodedUrl),
         Uri(http::uri::InvalidUri),
     }
@@ -267,6 +549,7 @@ mod error {
                 Self::ExpectedChar(c) => write!(f, "expected '{c}'"),
                 Self::EmptyConditionList => f.write_str("empty condition list"),
                 Self::EmptyResourceList => f.write_str("empty resource list"),
+                Self::MixedLists => f.write_str("tagged and untagged lists can't be mixed"),
                 Self::CodedUrl(..) => f.write_str("invalid Coded-URL"),
                 Self::Uri(..) => f.write_str("invalid URI"),
             }
@@ -378,3 +661,167 @@ fn test() {
         ),
     ]);
 }
+
+#[cfg(test)]
+#[test]
+fn evaluate() {
+    use std::collections::HashMap;
+
+    struct Resources(HashMap<&'static str, (&'static str, Vec<CodedUrl>)>);
+
+    impl ResourceState for Resources {
+        fn etag(&self, uri: &http::Uri) -> Option<String> {
+            self.0.get(uri.path()).map(|(etag, _)| etag.to_string())
+        }
+        fn lock_tokens(&self, uri: &http::Uri) -> Vec<CodedUrl> {
+            self.0
+                .get(uri.path())
+                .map(|(_, tokens)| tokens.clone())
+                .unwrap_or_default()
+        }
+    }
+
+    let token = |s: &str| CodedUrl(uniresid::AbsoluteUri::parse(s).unwrap());
+    let a = token("urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2");
+    let b = token("urn:uuid:58f202ac-22cf-11d1-b12d-002035b29092");
+    let resources = Resources(HashMap::from([
+        ("/locked", (r#""1""#, vec![a.clone()])),
+        ("/unlocked", (r#"W/"2""#, vec![])),
+    ]));
+
+    #[track_caller]
+    fn check(resources: &Resources, uri: &str, header: &str, matches: bool) -> IfEvaluation {
+        let evaluation = header
+            .parse::<If>()
+            .unwrap()
+            .evaluate(&uri.parse().unwrap(), resources);
+        assert_eq!(evaluation.matches, matches, "{header} on {uri}");
+        evaluation
+    }
+
+    let a_ = "<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>";
+    let b_ = "<urn:uuid:58f202ac-22cf-11d1-b12d-002035b29092>";
+
+    // AND within a list
+    check(&resources, "/locked", &format!(r#"({a_} ["1"])"#), true);
+    check(&resources, "/locked", &format!(r#"({a_} ["2"])"#), false);
+    // OR across lists
+    check(&resources, "/locked", &format!(r#"({b_}) (["1"])"#), true);
+    check(&resources, "/locked", &format!("({b_}) ({a_})"), true);
+    check(&resources, "/locked", &format!(r#"({b_}) (["2"])"#), false);
+    // `Not`
+    check(&resources, "/locked", &format!("(Not {b_})"), true);
+    check(&resources, "/locked", &format!("(Not {a_})"), false);
+    check(&resources, "/unlocked", "(Not <DAV:no-lock>)", true);
+    check(&resources, "/unlocked", "(<DAV:no-lock>)", false);
+    // entity tags are compared with the weak comparison function
+    check(&resources, "/unlocked", r#"([W/"2"])"#, true);
+    check(&resources, "/unlocked", r#"(["2"])"#, true);
+    check(&resources, "/locked", r#"([W/"1"])"#, true);
+    check(&resources, "/unlocked", r#"(Not ["2"])"#, false);
+    check(&resources, "/unlocked", r#"([W/"1"])"#, false);
+    // missing resources don't match anything
+    check(&resources, "/missing", r#"(["1"])"#, false);
+    check(&resources, "/missing", r#"(Not ["1"])"#, true);
+
+    // tagged lists apply to their resource instead of the request URI
+    check(&resources, "/unlocked", &format!("</locked> ({a_})"), true);
+    check(&resources, "/locked", &format!("</unlocked> ({a_})"), false);
+    check(
+        &resources,
+        "/unlocked",
+        &format!(r#"</unlocked> ({a_}) </locked> (["1"])"#),
+        true,
+    );
+
+    // submitted lock tokens
+    let evaluation = check(
+        &resources,
+        "/locked",
+        &format!(r#"({b_} ["1"]) (Not <DAV:no-lock> ["1"])"#),
+        true,
+    );
+    assert!(evaluation.is_submitted(&b));
+    assert!(!evaluation.is_submitted(&a));
+    assert!(!evaluation.is_submitted(&token("DAV:no-lock")));
+    let evaluation = check(&resources, "/locked", &format!("({a_}) (Not {b_})"), true);
+    assert_eq!(evaluation.submitted, HashSet::from([a]));
+}
+
+#[cfg(test)]
+#[test]
+fn builder() {
+    use nonempty::nonempty;
+    use pretty_assertions::assert_eq;
+
+    let token = CodedUrl(
+        uniresid::AbsoluteUri::parse("urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2").unwrap(),
+    );
+
+    let if_ = If::builder()
+        .lock_token(token.clone())
+        .etag(r#""I am an ETag""#)
+        .or()
+        .not_etag(r#""I am another ETag""#)
+        .build()
+        .unwrap();
+    assert_eq!(
+        if_,
+        If::NoTagList(Box::new(nonempty![
+            nonempty![
+                Condition::StateToken {
+                    not: false,
+                    coded_url: token.clone()
+                },
+                Condition::ETag {
+                    not: false,
+                    etag: r#""I am an ETag""#.into()
+                },
+            ],
+            nonempty![Condition::ETag {
+                not: true,
+                etag: r#""I am another ETag""#.into()
+            }],
+        ]))
+    );
+
+    let if_ = If::builder()
+        .resource("/resource1")
+        .lock_token(token.clone())
+        .resource("http://example.com/resource2")
+        .not_lock_token(token.clone())
+        .or()
+        .etag(r#"W/"A weak ETag""#)
+        .build()
+        .unwrap();
+    assert_eq!(
+        if_.to_string(),
+        r#"</resource1> (<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>) <http://example.com/resource2> (Not <urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>) ([W/"A weak ETag"])"#
+    );
+    assert_eq!(if_.to_string().parse::<If>().unwrap(), if_);
+
+    // tagged and untagged lists
+    assert!(matches!(
+        If::builder()
+            .etag("\"a\"")
+            .resource("/a")
+            .etag("\"b\"")
+            .build(),
+        Err(InvalidIf::MixedLists)
+    ));
+    // empty lists and resources
+    for builder in [
+        If::builder(),
+        If::builder().or().etag("\"a\""),
+        If::builder().resource("/a").resource("/b").etag("\"b\""),
+    ] {
+        assert!(matches!(
+            builder.build(),
+            Err(InvalidIf::EmptyConditionList)
+        ));
+    }
+    assert!(matches!(
+        If::builder().resource("/a b").etag("\"a\"").build(),
+        Err(InvalidIf::Uri(_))
+    ));
+}
@@ -6,30 +6,36 @@
 //! [RFC 4918](http://webdav.org/specs/rfc4918.html#http.headers.for.distributed.authoring)
 //! implementing the [`headers::Header`] trait.
 
+mod brief;
 mod dav;
 mod depth;
 mod destination;
 mod if_;
 mod lock_token;
 mod overwrite;
+mod prefer;
 mod timeout;
 mod utils;
 
 use self::utils::ParseString;
 
 pub use self::{
+    brief::Brief,
     coded_url::{CodedUrl, InvalidCodedUrl},
     dav::{ComplianceClass, Dav, InvalidComplianceClass, Tokens},
     depth::Depth,
-    destination::Destination,
-    if_::{Condition, If, InvalidIf, ResourceTag},
+    destination::{Destination, ResolveDestinationError},
+    if_::{Condition, If, IfBuilder, IfEvaluation, InvalidIf, ResourceState, ResourceTag},
     lock_token::LockToken,
     names::*,
     overwrite::Overwrite,
+    prefer::{ExtensionPreference, InvalidPreference, Prefer, Preference, PreferenceApplied},
     timeout::Timeout,
 };
 
 mod names {
+    /// Header name of the [`Brief`](super::Brief) header.
+    pub static BRIEF: headers::HeaderName = headers::HeaderName::from_static("brief");
     /// Header name of the [`DAV`](super::Dav) header.
     pub static DAV: headers::HeaderName = headers::HeaderName::from_static("dav");
     /// Header name of the [`Depth`](super::Depth) header.
@@ -42,6 +48,11 @@ mod names {
     pub static LOCK_TOKEN: headers::HeaderName = headers::HeaderName::from_static("lock-token");
     /// Header name of the [`Overwrite`](super::Overwrite) header.
     pub static OVERWRITE: headers::HeaderName = headers::HeaderName::from_static("overwrite");
+    /// Header name of the [`Prefer`](super::Prefer) header.
+    pub static PREFER: headers::HeaderName = headers::HeaderName::from_static("prefer");
+    /// Header name of the [`PreferenceApplied`](super::PreferenceApplied) header.
+    pub static PREFERENCE_APPLIED: headers::HeaderName =
+        headers::HeaderName::from_static("preference-applied");
     /// Header name of the [`Timeout`](super::Timeout) header.
     pub static TIMEOUT: headers::HeaderName = headers::HeaderName::from_static("timeout");
 }
@@ -52,7 +63,7 @@ mod coded_url {
     pub use self::error::InvalidCodedUrl;
 
     /// Coded-URL used in the `DAV` and `If` headers
-    #[derive(Clone, Debug, PartialEq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
     pub struct CodedUrl(pub uniresid::AbsoluteUri);
 
     impl std::fmt::Display for CodedUrl {
@@ -0,0 +1,515 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{utils::is_tchar, PREFER, PREFERENCE_APPLIED};
+
+pub use self::error::InvalidPreference;
+
+/// The `Prefer` header as defined in [RFC 7240](https://www.rfc-editor.org/rfc/rfc7240#section-2).
+///
+/// ```
+/// use headers::HeaderMapExt;
+/// use webdav_headers::{Prefer, Preference};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("prefer", "return=minimal, depth-noroot".parse()?);
+///
+/// let prefer = headers.typed_get::<Prefer>().unwrap();
+/// assert!(prefer.contains(&Preference::ReturnMinimal));
+/// assert!(prefer.contains(&Preference::DepthNoroot));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prefer(pub Vec<Preference>);
+
+impl Prefer {
+    /// Whether a preference was requested.
+    ///
+    /// Only the first occurrence of a preference is relevant, so
+    /// `return=representation, return=minimal` doesn't contain
+    /// [`Preference::ReturnMinimal`].
+    pub fn contains(&self, preference: &Preference) -> bool {
+        self.0
+            .iter()
+            .find(|p| p.name().eq_ignore_ascii_case(preference.name()))
+            .is_some_and(|p| p == preference)
+    }
+}
+
+impl headers::Header for Prefer {
+    fn name() -> &'static http::HeaderName {
+        &PREFER
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i http::HeaderValue>,
+    {
+        decode_list(values).map(Self)
+    }
+
+    fn encode<E: Extend<http::HeaderValue>>(&self, values: &mut E) {
+        encode_list(&self.0, values)
+    }
+}
+
+/// The `Preference-Applied` header as defined in [RFC 7240](https://www.rfc-editor.org/rfc/rfc7240#section-3).
+///
+/// Parameters of preferences aren't included in this header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferenceApplied(pub Vec<Preference>);
+
+impl headers::Header for PreferenceApplied {
+    fn name() -> &'static http::HeaderName {
+        &PREFERENCE_APPLIED
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i http::HeaderValue>,
+    {
+        decode_list(values).map(Self)
+    }
+
+    fn encode<E: Extend<http::HeaderValue>>(&self, values: &mut E) {
+        let preferences = self
+            .0
+            .iter()
+            .map(|preference| match preference {
+                Preference::Extension(extension) if !extension.parameters.is_empty() => {
+                    Preference::Extension(ExtensionPreference {
+                        parameters: Vec::new(),
+                        ..extension.clone()
+                    })
+                }
+                preference => preference.clone(),
+            })
+            .collect::<Vec<_>>();
+        encode_list(&preferences, values)
+    }
+}
+
+fn decode_list<'i>(
+    values: impl Iterator<Item = &'i http::HeaderValue>,
+) -> Result<Vec<Preference>, headers::Error> {
+    let mut preferences = Vec::new();
+    for value in values {
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        for preference in split_quoted(value, ',') {
+            let preference = preference.trim();
+            // empty list elements are allowed, and preferences that can't be
+            // parsed are ignored as required by RFC 7240
+            if let Ok(preference) = preference.parse() {
+                preferences.push(preference);
+            }
+        }
+    }
+    match preferences.is_empty() {
+        true => Err(headers::Error::invalid()),
+        false => Ok(preferences),
+    }
+}
+
+fn encode_list<E: Extend<http::HeaderValue>>(preferences: &[Preference], values: &mut E) {
+    values.extend(std::iter::once(
+        preferences.iter().join(", ").try_into().unwrap(),
+    ))
+}
+
+/// A preference used in the [`Prefer`] and [`PreferenceApplied`] headers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Preference {
+    /// `return=minimal`, e.g. to omit properties that weren't found from a
+    /// `PROPFIND` response as defined in [RFC 8144](https://www.rfc-editor.org/rfc/rfc8144#section-2.1)
+    ReturnMinimal,
+    /// `return=representation`
+    ReturnRepresentation,
+    /// `depth-noroot` as defined in [RFC 8144](https://www.rfc-editor.org/rfc/rfc8144#section-2.2)
+    /// to omit the target resource from a `PROPFIND` response
+    DepthNoroot,
+    /// `respond-async`
+    RespondAsync,
+    /// `wait` with the number of seconds, other values are read as
+    /// [`Preference::Extension`]
+    Wait(u64),
+    /// `handling=strict`
+    HandlingStrict,
+    /// `handling=lenient`
+    HandlingLenient,
+    /// Any other preference
+    Extension(ExtensionPreference),
+}
+
+impl Preference {
+    /// The name of the preference, e.g. `return`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::ReturnMinimal | Self::ReturnRepresentation => "return",
+            Self::DepthNoroot => "depth-noroot",
+            Self::RespondAsync => "respond-async",
+            Self::Wait(_) => "wait",
+            Self::HandlingStrict | Self::HandlingLenient => "handling",
+            Self::Extension(extension) => &extension.name,
+        }
+    }
+}
+
+impl Display for Preference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReturnMinimal => f.write_str("return=minimal"),
+            Self::ReturnRepresentation => f.write_str("return=representation"),
+            Self::DepthNoroot => f.write_str("depth-noroot"),
+            Self::RespondAsync => f.write_str("respond-async"),
+            Self::Wait(seconds) => write!(f, "wait={seconds}"),
+            Self::HandlingStrict => f.write_str("handling=strict"),
+            Self::HandlingLenient => f.write_str("handling=lenient"),
+            Self::Extension(extension) => extension.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Preference {
+    type Err = InvalidPreference;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = split_quoted(s, ';');
+        let (name, value) = parse_parameter(parts.next().unwrap_or_default())?;
+        let parameters = parts
+            .map(str::trim)
+            .filter(|parameter| !parameter.is_empty())
+            .map(|parameter| {
+                parse_parameter(parameter).map(|(name, value)| (name.to_owned(), value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let value = value.as_deref();
+        let name_is = |expected: &str| name.eq_ignore_ascii_case(expected);
+        if let Some(seconds) = value
+            .filter(|_| name_is("wait"))
+            .and_then(|v| v.parse().ok())
+        {
+            return Ok(Self::Wait(seconds));
+        }
+        // parameters of known preferences are ignored
+        Ok(match value {
+            Some(v) if name_is("return") && v.eq_ignore_ascii_case("minimal") => {
+                Self::ReturnMinimal
+            }
+            Some(v) if name_is("return") && v.eq_ignore_ascii_case("representation") => {
+                Self::ReturnRepresentation
+            }
+            None if name_is("depth-noroot") => Self::DepthNoroot,
+            None if name_is("respond-async") => Self::RespondAsync,
+            Some(v) if name_is("handling") && v.eq_ignore_ascii_case("strict") => {
+                Self::HandlingStrict
+            }
+            Some(v) if name_is("handling") && v.eq_ignore_ascii_case("lenient") => {
+                Self::HandlingLenient
+            }
+            _ => Self::Extension(ExtensionPreference {
+                name: name.to_owned(),
+                value: value.map(ToOwned::to_owned),
+                parameters,
+            }),
+        })
+    }
+}
+
+/// A preference that isn't known to this crate.
+///
+/// ```
+/// use webdav_headers::ExtensionPreference;
+///
+/// let preference = ExtensionPreference::new("foo", Some("a b"))?.with_parameter("bar", None)?;
+/// assert_eq!(preference.to_string(), r#"foo="a b"; bar"#);
+/// assert!(ExtensionPreference::new("foo", Some("a\nb")).is_err());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionPreference {
+    name: String,
+    value: Option<String>,
+    parameters: Vec<(String, Option<String>)>,
+}
+
+impl ExtensionPreference {
+    /// Create a preference.
+    ///
+    /// Fails if `name` isn't a token or `value` contains control characters.
+    pub fn new(name: impl Into<String>, value: Option<&str>) -> Result<Self, InvalidPreference> {
+        let name = name.into();
+        check_parameter(&name, value)?;
+        Ok(Self {
+            name,
+            value: value.map(ToOwned::to_owned),
+            parameters: Vec::new(),
+        })
+    }
+    /// Add a parameter, with the same requirements as
+    /// [`new`](Self::new).
+    pub fn with_parameter(
+        mut self,
+        name: impl Into<String>,
+        value: Option<&str>,
+    ) -> Result<Self, InvalidPreference> {
+        let name = name.into();
+        check_parameter(&name, value)?;
+        self.parameters.push((name, value.map(ToOwned::to_owned)));
+        Ok(self)
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+    /// Names and optional values of the parameters
+    pub fn parameters(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_deref()))
+    }
+}
+
+/// Check that a parameter can be written to a header value.
+fn check_parameter(name: &str, value: Option<&str>) -> Result<(), InvalidPreference> {
+    if !is_token(name) {
+        return Err(InvalidPreference::InvalidToken);
+    }
+    match value {
+        Some(value) if value.contains(|c: char| c.is_control() && c != '\t') => {
+            Err(InvalidPreference::InvalidQuotedString)
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Display for ExtensionPreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_parameter(f, &self.name, self.value.as_deref())?;
+        for (name, value) in &self.parameters {
+            f.write_str("; ")?;
+            fmt_parameter(f, name, value.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_parameter(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    value: Option<&str>,
+) -> std::fmt::Result {
+    f.write_str(name)?;
+    match value {
+        Some(value) if is_token(value) => write!(f, "={value}"),
+        Some(value) => {
+            f.write_str("=\"")?;
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+                write!(f, "{c}")?;
+            }
+            f.write_str("\"")
+        }
+        None => Ok(()),
+    }
+}
+
+/// Parse `token [ BWS "=" BWS word ]`.
+fn parse_parameter(s: &str) -> Result<(&str, Option<String>), InvalidPreference> {
+    let (name, value) = match s.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (s.trim(), None),
+    };
+    if !is_token(name) {
+        return Err(InvalidPreference::InvalidToken);
+    }
+    let value = match value {
+        Some(value) if value.starts_with('"') => Some(unquote(value)?),
+        Some(value) if is_token(value) => Some(value.to_owned()),
+        Some(_) => return Err(InvalidPreference::InvalidToken),
+        None => None,
+    };
+    Ok((name, value))
+}
+
+fn unquote(s: &str) -> Result<String, InvalidPreference> {
+    let mut chars = s.chars().skip(1);
+    let mut unquoted = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => break,
+            Some('\\') => {
+                unquoted.push(chars.next().ok_or(InvalidPreference::InvalidQuotedString)?)
+            }
+            Some(c) => unquoted.push(c),
+            None => return Err(InvalidPreference::InvalidQuotedString),
+        }
+    }
+    match chars.next() {
+        None => Ok(unquoted),
+        Some(_) => Err(InvalidPreference::InvalidQuotedString),
+    }
+}
+
+/// Split `s` at `separator`, except inside of quoted strings.
+fn split_quoted(s: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    let mut escaped = false;
+    s.split(move |c| {
+        match (quoted, escaped, c) {
+            (true, false, '\\') => escaped = true,
+            (true, true, _) => escaped = false,
+            (_, _, '"') => quoted = !quoted,
+            _ => {}
+        }
+        !quoted && c == separator
+    })
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_tchar)
+}
+
+mod error {
+    /// Error returned when parsing [`Preference`](super::Preference) from a
+    /// string fails.
+    #[derive(Debug)]
+    pub enum InvalidPreference {
+        InvalidToken,
+        InvalidQuotedString,
+    }
+
+    impl std::fmt::Display for InvalidPreference {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::InvalidToken => f.write_str("invalid token"),
+                Self::InvalidQuotedString => f.write_str("invalid quoted string"),
+            }
+        }
+    }
+
+    impl std::error::Error for InvalidPreference {}
+
+    impl From<InvalidPreference> for headers::Error {
+        fn from(_: InvalidPreference) -> Self {
+            headers::Error::invalid()
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    use crate::test::{test_all, test_decode};
+
+    test_all([
+        (
+            "return=minimal, depth-noroot",
+            Prefer(vec![Preference::ReturnMinimal, Preference::DepthNoroot]),
+        ),
+        (
+            "return=representation, respond-async, wait=100, handling=lenient",
+            Prefer(vec![
+                Preference::ReturnRepresentation,
+                Preference::RespondAsync,
+                Preference::Wait(100),
+                Preference::HandlingLenient,
+            ]),
+        ),
+        (
+            r#"foo; bar, baz="a, \"b\"; c"; qux=1"#,
+            Prefer(vec![
+                Preference::Extension(ExtensionPreference {
+                    name: "foo".into(),
+                    value: None,
+                    parameters: vec![("bar".into(), None)],
+                }),
+                Preference::Extension(ExtensionPreference {
+                    name: "baz".into(),
+                    value: Some(r#"a, "b"; c"#.into()),
+                    parameters: vec![("qux".into(), Some("1".into()))],
+                }),
+            ]),
+        ),
+    ]);
+
+    // case, whitespace, parameters of known preferences and multiple values
+    assert_eq!(
+        test_decode::<Prefer>(&["RETURN = \"minimal\"; foo=bar,, handling=STRICT", "Wait=5"]),
+        Some(Prefer(vec![
+            Preference::ReturnMinimal,
+            Preference::HandlingStrict,
+            Preference::Wait(5),
+        ]))
+    );
+    for invalid in ["", " , ", "return=", "a b", r#"foo="bar"#, "foo=\"a\"b"] {
+        assert_eq!(test_decode::<Prefer>(&[invalid]), None, "{invalid}");
+    }
+
+    // preferences that can't be parsed are ignored
+    assert_eq!(
+        test_decode::<Prefer>(&["return=minimal, a b, foo=\"a\"b, depth-noroot"]),
+        Some(Prefer(vec![
+            Preference::ReturnMinimal,
+            Preference::DepthNoroot
+        ]))
+    );
+    // an invalid `wait` is an extension preference
+    assert_eq!(
+        test_decode::<Prefer>(&["return=minimal, wait=soon"]),
+        Some(Prefer(vec![
+            Preference::ReturnMinimal,
+            Preference::Extension(ExtensionPreference::new("wait", Some("soon")).unwrap()),
+        ]))
+    );
+
+    let prefer = test_decode::<Prefer>(&["return=representation, return=minimal"]).unwrap();
+    assert!(prefer.contains(&Preference::ReturnRepresentation));
+    assert!(!prefer.contains(&Preference::ReturnMinimal));
+
+    test_all([(
+        "return=minimal, foo",
+        PreferenceApplied(vec![
+            Preference::ReturnMinimal,
+            Preference::Extension(ExtensionPreference {
+                name: "foo".into(),
+                value: None,
+                parameters: Vec::new(),
+            }),
+        ]),
+    )]);
+    assert_eq!(
+        crate::test::test_encode(PreferenceApplied(vec![Preference::Extension(
+            ExtensionPreference {
+                name: "foo".into(),
+                value: Some("a b".into()),
+                parameters: vec![("bar".into(), None)],
+            }
+        )]))[&PREFERENCE_APPLIED],
+        r#"foo="a b""#
+    );
+
+    // values that can't be encoded can't be built
+    assert!(ExtensionPreference::new("a b", None).is_err());
+    assert!(ExtensionPreference::new("foo", Some("a\nb")).is_err());
+    assert!(ExtensionPreference::new("foo", None)
+        .unwrap()
+        .with_parameter("bar", Some("\0"))
+        .is_err());
+    let preference = ExtensionPreference::new("foo", Some("a\tb")).unwrap();
+    assert_eq!(
+        crate::test::test_encode(Prefer(vec![Preference::Extension(preference)]))[&PREFER],
+        "foo=\"a\tb\""
+    );
+}
@@ -69,3 +69,25 @@ impl StrExt for str {
             .then_some(&self[s.len()..])
     }
 }
+
+/// Whether `c` is a `tchar` as defined in RFC 7230.
+pub(crate) fn is_tchar(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '!' | '#'
+                | '$'
+                | '%'
+                | '&'
+                | '\''
+                | '*'
+                | '+'
+                | '-'
+                | '.'
+                | '^'
+                | '_'
+                | '`'
+                | '|'
+                | '~'
+        )
+}
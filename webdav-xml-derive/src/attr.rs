@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use proc_macro2::Span;
+use syn::{Attribute, Expr, Ident, LitStr, Path};
+
+/// Attributes on the struct or enum itself, e.g.
+/// `#[xml(namespace = "DAV:", prefix = "d", local_name = "activelock")]`.
+pub(crate) struct ContainerAttrs {
+    pub krate: Path,
+    pub namespace: Option<Expr>,
+    pub prefix: Option<Expr>,
+    pub local_name: Option<Expr>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut slf = Self {
+            krate: syn::parse_quote!(::webdav_xml),
+            namespace: None,
+            prefix: None,
+            local_name: None,
+        };
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("xml")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    slf.krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                } else if meta.path.is_ident("namespace") {
+                    slf.namespace = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("prefix") {
+                    slf.prefix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("local_name") {
+                    slf.local_name = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown `xml` attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(slf)
+    }
+}
+
+/// How a struct field maps to child elements.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum FieldKind {
+    /// Exactly one child element, e.g. `lockscope` in `activelock`.
+    Required,
+    /// Zero or one child element, stored as `Option<T>`.
+    Optional,
+    /// One or more child elements, stored as `NonEmpty<T>`.
+    NonEmpty,
+    /// Zero or more child elements, stored as `Vec<T>`.
+    List,
+}
+
+impl FieldKind {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut kind = None;
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("xml")) {
+            attr.parse_nested_meta(|meta| {
+                let new_kind = if meta.path.is_ident("optional") {
+                    FieldKind::Optional
+                } else if meta.path.is_ident("nonempty") {
+                    FieldKind::NonEmpty
+                } else if meta.path.is_ident("list") {
+                    FieldKind::List
+                } else {
+                    return Err(meta.error("unknown `xml` attribute"));
+                };
+                match kind.replace(new_kind) {
+                    Some(_) => Err(meta
+                        .error("only one of `optional`, `nonempty` and `list` may be specified")),
+                    None => Ok(()),
+                }
+            })?;
+        }
+
+        Ok(kind.unwrap_or(FieldKind::Required))
+    }
+}
+
+/// Attributes on an enum variant, e.g. `#[xml(local_name = "exclusive")]`.
+pub(crate) struct VariantAttrs {
+    pub local_name: Option<Expr>,
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut slf = Self { local_name: None };
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("xml")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("local_name") {
+                    slf.local_name = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown `xml` attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(slf)
+    }
+}
+
+/// The default local name of an element is its lowercased identifier, e.g.
+/// `ActiveLock` becomes `activelock`.
+pub(crate) fn default_local_name(ident: &Ident) -> Expr {
+    let name = LitStr::new(&ident.to_string().to_lowercase(), Span::call_site());
+    syn::parse_quote!(#name)
+}
@@ -0,0 +1,278 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Derive macros for the `Element`, `TryFrom<&Value>` and `Into<Value>`
+//! implementations used by [`webdav-xml`](https://docs.rs/webdav-xml).
+//!
+//! This crate shouldn't be used directly. Enable the `derive` feature of
+//! `webdav-xml` instead.
+
+mod attr;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, Member};
+
+use self::attr::{default_local_name, ContainerAttrs, FieldKind, VariantAttrs};
+
+/// Implements `Element` for a struct or enum.
+///
+/// `namespace` and `prefix` are required. `local_name` defaults to the
+/// lowercased name of the type.
+///
+/// ```ignore
+/// #[derive(Element)]
+/// #[xml(namespace = "http://owncloud.org/ns", prefix = "oc", local_name = "share-types")]
+/// struct ShareTypes;
+/// ```
+#[proc_macro_derive(Element, attributes(xml))]
+pub fn derive_element(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_element(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `TryFrom<&Value>` for a struct or enum.
+///
+/// Each field of a struct is extracted from the child element of the field's
+/// type. By default, the child element is required. Use `#[xml(optional)]`
+/// for `Option<T>`, `#[xml(nonempty)]` for `NonEmpty<T>` and `#[xml(list)]`
+/// for `Vec<T>` fields. Unit structs accept any content.
+///
+/// An enum requires exactly one of its variants to be present. Unit variants
+/// are matched against an empty child element in the namespace of the enum
+/// itself (named after the lowercased variant unless `#[xml(local_name =
+/// "...")]` is given), tuple variants with a single field against the child
+/// element of the field's type.
+#[proc_macro_derive(FromValue, attributes(xml))]
+pub fn derive_from_value(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_from_value(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `From<T> for Value` for a struct or enum.
+///
+/// Uses the same attributes as [`FromValue`](derive@FromValue).
+#[proc_macro_derive(IntoValue, attributes(xml))]
+pub fn derive_into_value(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_into_value(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_element(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = ContainerAttrs::parse(&input.attrs)?;
+    let krate = &attrs.krate;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Some(namespace) = &attrs.namespace else {
+        return Err(syn::Error::new(
+            ident.span(),
+            "missing `#[xml(namespace = ...)]` attribute",
+        ));
+    };
+    let Some(prefix) = &attrs.prefix else {
+        return Err(syn::Error::new(
+            ident.span(),
+            "missing `#[xml(prefix = ...)]` attribute",
+        ));
+    };
+    let local_name = attrs
+        .local_name
+        .clone()
+        .unwrap_or_else(|| default_local_name(ident));
+
+    Ok(quote! {
+        impl #impl_generics #krate::Element for #ident #ty_generics #where_clause {
+            const NAMESPACE: &'static str = #namespace;
+            const PREFIX: &'static str = #prefix;
+            const LOCAL_NAME: &'static str = #local_name;
+        }
+    })
+}
+
+fn expand_from_value(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = ContainerAttrs::parse(&input.attrs)?;
+    let krate = &attrs.krate;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => quote! {
+            let _ = value;
+            ::core::result::Result::Ok(Self)
+        },
+        Data::Struct(data) => {
+            let fields = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let member = member(i, field);
+                    let extract = match FieldKind::parse(&field.attrs)? {
+                        FieldKind::Required => quote!(required),
+                        FieldKind::Optional => quote!(optional),
+                        FieldKind::NonEmpty => quote!(nonempty),
+                        FieldKind::List => quote!(list),
+                    };
+                    Ok(quote!(#member: #krate::__private::#extract(map)?))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                let map = value.to_map()?;
+                ::core::result::Result::Ok(Self { #(#fields,)* })
+            }
+        }
+        Data::Enum(data) => {
+            let mut names = Vec::new();
+            let mut matchers = Vec::new();
+
+            for variant in &data.variants {
+                let variant_ident = &variant.ident;
+                match &variant.fields {
+                    Fields::Unit => {
+                        let local_name = VariantAttrs::parse(&variant.attrs)?
+                            .local_name
+                            .unwrap_or_else(|| default_local_name(variant_ident));
+                        names.push(quote!(#local_name));
+                        matchers.push(quote! {
+                            if #krate::__private::contains::<Self>(map, #local_name) {
+                                found.push(Self::#variant_ident);
+                            }
+                        });
+                    }
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        let ty = &fields.unnamed[0].ty;
+                        names.push(quote!(<#ty as #krate::Element>::LOCAL_NAME));
+                        matchers.push(quote! {
+                            if let ::core::option::Option::Some(inner) = map.get::<#ty>() {
+                                found.push(Self::#variant_ident(inner?));
+                            }
+                        });
+                    }
+                    _ => return Err(syn::Error::new(
+                        variant.span(),
+                        "only unit variants and tuple variants with a single field are supported",
+                    )),
+                }
+            }
+
+            quote! {
+                const NAMES: &[&str] = &[#(#names),*];
+                let map = value.to_map()?;
+                let mut found = ::std::vec::Vec::with_capacity(1);
+                #(#matchers)*
+                #krate::__private::exactly_one(found, NAMES)
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(input.span(), "unions are not supported")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::TryFrom<&#krate::Value> for #ident #ty_generics #where_clause {
+            type Error = #krate::ExtractElementError;
+
+            fn try_from(value: &#krate::Value) -> ::core::result::Result<Self, Self::Error> {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_into_value(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = ContainerAttrs::parse(&input.attrs)?;
+    let krate = &attrs.krate;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => quote! {
+            let _ = element;
+            #krate::Value::Empty
+        },
+        Data::Struct(data) => {
+            let inserts = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let member = member(i, field);
+                    Ok(match FieldKind::parse(&field.attrs)? {
+                        FieldKind::Required => {
+                            quote!(#krate::__private::insert(&mut map, element.#member);)
+                        }
+                        FieldKind::Optional | FieldKind::NonEmpty | FieldKind::List => {
+                            quote!(#krate::__private::insert_all(&mut map, element.#member);)
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                let mut map = #krate::ValueMap::new();
+                #(#inserts)*
+                #krate::Value::Map(map)
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    match &variant.fields {
+                        Fields::Unit => {
+                            let local_name = VariantAttrs::parse(&variant.attrs)?
+                                .local_name
+                                .unwrap_or_else(|| default_local_name(variant_ident));
+                            Ok(quote! {
+                                #ident::#variant_ident => {
+                                    #krate::__private::insert_empty::<#ident #ty_generics>(&mut map, #local_name)
+                                }
+                            })
+                        }
+                        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(quote! {
+                            #ident::#variant_ident(inner) => #krate::__private::insert(&mut map, inner)
+                        }),
+                        _ => Err(syn::Error::new(
+                            variant.span(),
+                            "only unit variants and tuple variants with a single field are supported",
+                        )),
+                    }
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                let mut map = #krate::ValueMap::new();
+                match element {
+                    #(#arms,)*
+                }
+                #krate::Value::Map(map)
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(input.span(), "unions are not supported")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #krate::Value #where_clause {
+            fn from(element: #ident #ty_generics) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+fn member(index: usize, field: &syn::Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(index.into()),
+    }
+}
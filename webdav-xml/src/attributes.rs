@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use bytestring::ByteString;
+use indexmap::IndexMap;
+
+use crate::element::ElementName;
+
+/// The namespace bound to the reserved `xml` prefix
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+/// The reserved prefix of the [`XML_NAMESPACE`]
+pub const XML_PREFIX: &str = "xml";
+
+/// The attributes of an XML element, e.g. `xml:lang="en"`.
+///
+/// Namespace declarations (`xmlns`, `xmlns:*`) aren't stored here, as they
+/// are resolved while reading and generated while writing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes(pub(crate) IndexMap<ElementName<ByteString>, ByteString>);
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Read an attribute by its namespace and local name.
+    ///
+    /// Attributes without a prefix don't have a namespace, so `namespace`
+    /// should be `None` for them.
+    pub fn get(&self, namespace: Option<&str>, local_name: &str) -> Option<&ByteString> {
+        self.0.get(&ElementName {
+            namespace,
+            prefix: None,
+            local_name,
+        })
+    }
+    /// Insert an attribute, replacing an existing attribute with the same
+    /// name.
+    ///
+    /// `prefix` is only used as a hint for the namespace prefix when writing.
+    pub fn insert(
+        &mut self,
+        namespace: Option<impl Into<ByteString>>,
+        prefix: Option<impl Into<ByteString>>,
+        local_name: impl Into<ByteString>,
+        value: impl Into<ByteString>,
+    ) {
+        self.0.insert(
+            ElementName {
+                namespace: namespace.map(Into::into),
+                prefix: prefix.map(Into::into),
+                local_name: local_name.into(),
+            },
+            value.into(),
+        );
+    }
+    /// Remove an attribute by its namespace and local name.
+    pub fn remove(&mut self, namespace: Option<&str>, local_name: &str) -> Option<ByteString> {
+        self.0.shift_remove(&ElementName {
+            namespace,
+            prefix: None,
+            local_name,
+        })
+    }
+    /// Read the `xml:lang` attribute.
+    pub fn lang(&self) -> Option<&ByteString> {
+        self.get(Some(XML_NAMESPACE), "lang")
+    }
+    /// Set the `xml:lang` attribute.
+    pub fn set_lang(&mut self, lang: impl Into<ByteString>) {
+        self.insert(Some(XML_NAMESPACE), Some(XML_PREFIX), "lang", lang)
+    }
+    /// Iterate over the names and values of all attributes.
+    pub fn iter(&self) -> impl Iterator<Item = (&ElementName<ByteString>, &ByteString)> {
+        self.0.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{future::Future, pin::pin};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http_body::Body;
+
+use crate::{FromXml, Limit, ReadLimits, XmlError, XmlErrorKind};
+
+/// The number of bytes that are read at once from an `AsyncRead`.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Performs deserialization from an HTTP body or an `AsyncRead`.
+///
+/// This trait is implemented for every type that implements [`FromXml`]. The
+/// input is collected until it exceeds the maximum size, which fails with
+/// [`Limit::Size`], and then read like [`FromXml::from_xml_with`] does.
+///
+/// ```
+/// use webdav_xml::{elements::Propfind, FromBody};
+///
+/// # futures::executor::block_on(async {
+/// let body = http_body_util::Full::new(bytes::Bytes::from_static(
+///     br#"<d:propfind xmlns:d="DAV:"><d:propname/></d:propfind>"#,
+/// ));
+/// let propfind = Propfind::from_body(body, 64 * 1024).await?;
+/// assert_eq!(propfind, Propfind::Propname);
+/// # Ok::<(), webdav_xml::Error>(())
+/// # }).unwrap();
+/// ```
+pub trait FromBody: FromXml {
+    /// Read an [`http_body::Body`] of at most `max_size` bytes, e.g. the
+    /// request body of `axum` or `hyper` or the response body of `reqwest`.
+    fn from_body<B>(body: B, max_size: usize) -> impl Future<Output = crate::Result<Self>> + Send
+    where
+        B: Body + Send,
+        B::Data: Send,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let limits = ReadLimits::default().max_size(max_size);
+        async move { Self::from_body_with(body, &limits).await }
+    }
+    /// Like [`from_body`](Self::from_body), with all of the [`ReadLimits`].
+    fn from_body_with<B>(
+        body: B,
+        limits: &ReadLimits,
+    ) -> impl Future<Output = crate::Result<Self>> + Send
+    where
+        B: Body + Send,
+        B::Data: Send,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let limits = limits.clone();
+        async move {
+            let max_size = limits.get_max_size();
+            // reject a body with a known `Content-Length` without reading it
+            if body.size_hint().lower() > max_size as u64 {
+                return Err(size_exceeded(max_size).into());
+            }
+
+            let mut body = pin!(body);
+            let mut xml = BytesMut::new();
+            while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+                let Ok(data) = frame.map_err(read_error)?.into_data() else {
+                    // trailers
+                    continue;
+                };
+                if xml.len() + data.remaining() > max_size {
+                    return Err(size_exceeded(max_size).into());
+                }
+                xml.put(data);
+            }
+
+            Self::from_xml_with(xml.freeze(), &limits)
+        }
+    }
+    /// Read at most `max_size` bytes from a `tokio::io::AsyncRead`.
+    fn from_async_read<R>(
+        reader: R,
+        max_size: usize,
+    ) -> impl Future<Output = crate::Result<Self>> + Send
+    where
+        R: tokio::io::AsyncRead + Send,
+    {
+        let limits = ReadLimits::default().max_size(max_size);
+        async move { Self::from_async_read_with(reader, &limits).await }
+    }
+    /// Like [`from_async_read`](Self::from_async_read), with all of the
+    /// [`ReadLimits`].
+    fn from_async_read_with<R>(
+        reader: R,
+        limits: &ReadLimits,
+    ) -> impl Future<Output = crate::Result<Self>> + Send
+    where
+        R: tokio::io::AsyncRead + Send,
+    {
+        let limits = limits.clone();
+        async move {
+            let max_size = limits.get_max_size();
+
+            let mut reader = pin!(reader);
+            let mut xml = BytesMut::new();
+            let mut buf = [0; CHUNK_SIZE];
+            loop {
+                let mut read_buf = tokio::io::ReadBuf::new(&mut buf);
+                std::future::poll_fn(|cx| reader.as_mut().poll_read(cx, &mut read_buf))
+                    .await
+                    .map_err(read_error)?;
+                match read_buf.filled() {
+                    [] => break,
+                    chunk if xml.len() + chunk.len() > max_size => {
+                        return Err(size_exceeded(max_size).into())
+                    }
+                    chunk => xml.extend_from_slice(chunk),
+                }
+            }
+
+            Self::from_xml_with(Bytes::from(xml), &limits)
+        }
+    }
+}
+
+impl<T: FromXml> FromBody for T {}
+
+fn size_exceeded(max_size: usize) -> XmlError {
+    XmlErrorKind::LimitExceeded(Limit::Size(max_size)).into()
+}
+
+fn read_error(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> XmlError {
+    XmlErrorKind::Read(e.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, stream};
+    use http_body_util::{Full, StreamBody};
+
+    use super::*;
+    use crate::{elements::Propfind, Error};
+
+    const XML: &[u8] = br#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propfind xmlns:D="DAV:">
+  <D:propname/>
+</D:propfind>
+"#;
+
+    fn limit(result: crate::Result<Propfind>) -> Option<Limit> {
+        match result {
+            Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::LimitExceeded(limit),
+                ..
+            })) => Some(limit),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn body() -> eyre::Result<()> {
+        let chunks = XML
+            .chunks(7)
+            .map(|chunk| Ok::<_, std::io::Error>(http_body::Frame::data(Bytes::from(chunk))));
+        let body = StreamBody::new(stream::iter(chunks));
+        assert_eq!(
+            block_on(Propfind::from_body(body, XML.len()))?,
+            Propfind::Propname
+        );
+
+        let chunks = XML
+            .chunks(7)
+            .map(|chunk| Ok::<_, std::io::Error>(http_body::Frame::data(Bytes::from(chunk))));
+        let body = StreamBody::new(stream::iter(chunks));
+        assert_eq!(
+            limit(block_on(Propfind::from_body(body, XML.len() - 1))),
+            Some(Limit::Size(XML.len() - 1))
+        );
+
+        // rejected based on the size hint
+        let body = Full::new(Bytes::from_static(XML));
+        assert_eq!(
+            limit(block_on(Propfind::from_body(body, 16))),
+            Some(Limit::Size(16))
+        );
+
+        let chunks = [
+            Ok(http_body::Frame::data(Bytes::from_static(&XML[..10]))),
+            Err(std::io::Error::other("connection reset")),
+        ];
+        let body = StreamBody::new(stream::iter(chunks));
+        match block_on(Propfind::from_body(body, 1024)) {
+            Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::Read(e),
+                ..
+            })) => assert_eq!(e.to_string(), "connection reset"),
+            result => panic!("expected a read error, got {result:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn async_read() -> eyre::Result<()> {
+        assert_eq!(
+            block_on(Propfind::from_async_read(XML, XML.len()))?,
+            Propfind::Propname
+        );
+        assert_eq!(
+            limit(block_on(Propfind::from_async_read(XML, 16))),
+            Some(Limit::Size(16))
+        );
+
+        let limits = ReadLimits::new().max_depth(1);
+        assert_eq!(
+            limit(block_on(Propfind::from_async_read_with(XML, &limits))),
+            Some(Limit::Depth(1))
+        );
+
+        Ok(())
+    }
+}
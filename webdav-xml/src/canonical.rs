@@ -0,0 +1,464 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use indexmap::IndexMap;
+use nonempty::NonEmpty;
+
+use crate::{
+    element::ElementName,
+    value::{InnerValueMap, Node},
+    Attributes, Value, ValueMap, XML_NAMESPACE, XML_PREFIX,
+};
+
+impl Value {
+    /// Normalize this value, so that values that represent the same XML are
+    /// equal.
+    ///
+    /// In the canonical form
+    ///
+    /// - text that only contains whitespace and maps without elements are
+    ///   [`Value::Empty`],
+    /// - mixed content without text besides whitespace is a [`Value::Map`],
+    ///   and adjacent text nodes are merged,
+    /// - elements and attributes are sorted by their namespace and local name
+    ///   and don't have a prefix,
+    /// - a [`Value::List`] has more than one element.
+    ///
+    /// The order of elements with the same name and the text of elements
+    /// with text content are preserved.
+    pub fn canonicalize(&self) -> Value {
+        match self {
+            Value::Empty => Value::Empty,
+            Value::Text(text) if is_whitespace(text) => Value::Empty,
+            Value::Text(text) => Value::Text(text.clone()),
+            Value::Map(map) => canonicalize_map(map.iter()),
+            Value::List(list) => {
+                let mut list = list.iter().map(Value::canonicalize);
+                let head = list.next().expect("lists are not empty");
+                match list.collect::<Vec<_>>() {
+                    tail if tail.is_empty() => head,
+                    tail => Value::List(Box::new(NonEmpty { head, tail })),
+                }
+            }
+            Value::Mixed(nodes) => canonicalize_mixed(nodes),
+            Value::Attributed(attributed) => {
+                let (attributes, value) = &**attributed;
+                let mut attributes = attributes
+                    .iter()
+                    .map(|(name, value)| (without_prefix(name), value.clone()))
+                    .collect::<IndexMap<_, _>>();
+                attributes.sort_by(|a, _, b, _| cmp_names(a, b));
+                value.canonicalize().with_attributes(Attributes(attributes))
+            }
+        }
+    }
+    /// Compare two values while ignoring namespace prefixes, the order of
+    /// elements with different names and insignificant whitespace.
+    ///
+    /// This is equivalent to comparing the [canonical](Self::canonicalize)
+    /// forms, e.g. to detect a `PROPPATCH` that doesn't change a dead
+    /// property:
+    ///
+    /// ```
+    /// use webdav_xml::{FromXml, Value};
+    ///
+    /// let a = Value::from_xml(r#"<a:x xmlns:a="urn:example"><a:y>1</a:y><a:z> </a:z></a:x>"#)?;
+    /// let b = Value::from_xml(r#"<b:x xmlns:b="urn:example"><b:z></b:z><b:y>1</b:y></b:x>"#)?;
+    /// assert_ne!(a, b);
+    /// assert!(a.semantically_eq(&b));
+    /// # Ok::<(), webdav_xml::Error>(())
+    /// ```
+    pub fn semantically_eq(&self, other: &Value) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+    /// Serialize the content of this value as canonical XML in the style of
+    /// [Exclusive XML Canonicalization](https://www.w3.org/TR/xml-exc-c14n/).
+    ///
+    /// The output doesn't depend on the namespace prefixes, the order of
+    /// elements with different names or insignificant whitespace, so values
+    /// are [semantically equal](Self::semantically_eq) exactly if their
+    /// canonical XML is equal. Namespaces are bound to the prefixes `ns0`,
+    /// `ns1`, … in the order they first appear and are declared on the
+    /// outermost elements that use them. Elements are never self-closing.
+    ///
+    /// A document that was read with [`FromXml`](crate::FromXml) results in
+    /// the complete document. Attributes of this value itself aren't included,
+    /// as they belong to the element that contains it.
+    ///
+    /// ```
+    /// use webdav_xml::{FromXml, Value};
+    ///
+    /// let value = Value::from_xml(
+    ///     r#"<D:prop xmlns:D="DAV:" xmlns:Z="urn:example">
+    ///          <Z:author lang='en'>Jane &amp; John</Z:author>
+    ///          <D:displayname/>
+    ///        </D:prop>"#,
+    /// )?;
+    /// assert_eq!(
+    ///     value.to_canonical_xml(),
+    ///     r#"<ns0:prop xmlns:ns0="DAV:"><ns0:displayname></ns0:displayname><ns1:author xmlns:ns1="urn:example" lang="en">Jane &amp; John</ns1:author></ns0:prop>"#
+    /// );
+    /// # Ok::<(), webdav_xml::Error>(())
+    /// ```
+    pub fn to_canonical_xml(&self) -> Bytes {
+        let mut writer = CanonicalWriter::default();
+        writer.write_content(&self.canonicalize());
+        Bytes::from(writer.output)
+    }
+}
+
+fn is_whitespace(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+fn without_prefix(name: &ElementName<ByteString>) -> ElementName<ByteString> {
+    ElementName {
+        prefix: None,
+        ..name.clone()
+    }
+}
+
+fn cmp_names(a: &ElementName<ByteString>, b: &ElementName<ByteString>) -> std::cmp::Ordering {
+    (a.namespace.as_deref(), &*a.local_name).cmp(&(b.namespace.as_deref(), &*b.local_name))
+}
+
+fn canonicalize_map<'v>(
+    elements: impl Iterator<Item = (&'v ElementName<ByteString>, &'v Value)>,
+) -> Value {
+    sort_map(
+        elements
+            .map(|(name, value)| (without_prefix(name), value.canonicalize()))
+            .collect(),
+    )
+}
+
+fn sort_map(mut map: InnerValueMap) -> Value {
+    if map.is_empty() {
+        return Value::Empty;
+    }
+    map.sort_by(|a, _, b, _| cmp_names(a, b));
+    Value::Map(ValueMap(map))
+}
+
+fn canonicalize_mixed(nodes: &[Node]) -> Value {
+    let mut canonical = Vec::<Node>::with_capacity(nodes.len());
+    // adjacent text nodes are collected before they are added
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(name, value) => {
+                if !text.is_empty() {
+                    canonical.push(Node::Text(std::mem::take(&mut text).into()));
+                }
+                canonical.push(Node::Element(without_prefix(name), value.canonicalize()))
+            }
+        }
+    }
+    if !text.is_empty() {
+        canonical.push(Node::Text(text.into()));
+    }
+
+    let has_text = canonical
+        .iter()
+        .any(|node| matches!(node, Node::Text(text) if !is_whitespace(text)));
+    let has_elements = canonical
+        .iter()
+        .any(|node| matches!(node, Node::Element(..)));
+    match (has_text, has_elements) {
+        (true, true) => Value::Mixed(canonical),
+        (true, false) => match canonical.into_iter().next() {
+            Some(Node::Text(text)) => Value::Text(text),
+            _ => Value::Empty,
+        },
+        (false, _) => {
+            let mut map = ValueMap::new();
+            for node in canonical {
+                if let Node::Element(name, value) = node {
+                    map.insert_by_name(name, value);
+                }
+            }
+            sort_map(map.0)
+        }
+    }
+}
+
+/// Writes canonical values as XML.
+#[derive(Default)]
+struct CanonicalWriter {
+    output: String,
+    /// The prefixes that were bound to namespaces so far
+    prefixes: IndexMap<ByteString, ByteString>,
+    /// The namespaces that are declared on the ancestors of the current
+    /// element
+    scope: Vec<ByteString>,
+}
+
+impl CanonicalWriter {
+    fn write_content(&mut self, value: &Value) {
+        match value {
+            Value::Empty => {}
+            Value::Text(text) => escape_text(&mut self.output, text),
+            Value::Map(map) => {
+                for (name, value) in map.iter() {
+                    self.write_elements(name, value);
+                }
+            }
+            Value::List(list) => {
+                for value in list.iter() {
+                    self.write_content(value);
+                }
+            }
+            Value::Mixed(nodes) => {
+                for node in nodes {
+                    match node {
+                        Node::Text(text) => escape_text(&mut self.output, text),
+                        Node::Element(name, value) => self.write_elements(name, value),
+                    }
+                }
+            }
+            Value::Attributed(attributed) => self.write_content(&attributed.1),
+        }
+    }
+    fn write_elements(&mut self, name: &ElementName<ByteString>, value: &Value) {
+        match value {
+            Value::List(list) => {
+                for value in list.iter() {
+                    self.write_element(name, value);
+                }
+            }
+            value => self.write_element(name, value),
+        }
+    }
+    fn write_element(&mut self, name: &ElementName<ByteString>, value: &Value) {
+        let scope = self.scope.len();
+        let attributes = value
+            .attributes()
+            .into_iter()
+            .flat_map(Attributes::iter)
+            .collect::<Vec<_>>();
+
+        // declare the namespaces that are used by this element and not by
+        // an ancestor
+        let mut declarations = Vec::new();
+        for namespace in std::iter::once(name)
+            .chain(attributes.iter().map(|(name, _)| *name))
+            .filter_map(|name| name.namespace.as_ref())
+        {
+            if namespace == XML_NAMESPACE || self.scope.contains(namespace) {
+                continue;
+            }
+            let prefix = self.prefix(namespace);
+            self.scope.push(namespace.clone());
+            declarations.push((prefix, namespace.clone()));
+        }
+        declarations.sort();
+
+        let qname = self.qname(name);
+        self.output.push('<');
+        self.output.push_str(&qname);
+        for (prefix, namespace) in declarations {
+            self.output.push_str(" xmlns:");
+            self.output.push_str(&prefix);
+            self.output.push_str("=\"");
+            escape_attribute(&mut self.output, &namespace);
+            self.output.push('"');
+        }
+        for (name, value) in attributes {
+            let qname = self.qname(name);
+            self.output.push(' ');
+            self.output.push_str(&qname);
+            self.output.push_str("=\"");
+            escape_attribute(&mut self.output, value);
+            self.output.push('"');
+        }
+        self.output.push('>');
+
+        self.write_content(value.content());
+
+        self.output.push_str("</");
+        self.output.push_str(&qname);
+        self.output.push('>');
+        self.scope.truncate(scope);
+    }
+    fn prefix(&mut self, namespace: &ByteString) -> ByteString {
+        let next = self.prefixes.len();
+        self.prefixes
+            .entry(namespace.clone())
+            .or_insert_with(|| format!("ns{next}").into())
+            .clone()
+    }
+    fn qname(&self, name: &ElementName<ByteString>) -> String {
+        match &name.namespace {
+            Some(namespace) if namespace == XML_NAMESPACE => {
+                format!("{XML_PREFIX}:{}", name.local_name)
+            }
+            Some(namespace) => format!("{}:{}", self.prefixes[namespace], name.local_name),
+            None => name.local_name.to_string(),
+        }
+    }
+}
+
+fn escape_text(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+fn escape_attribute(output: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' => output.push_str("&#x9;"),
+            '\n' => output.push_str("&#xA;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::{assert_eq, assert_str_eq};
+
+    use super::*;
+    use crate::FromXml;
+
+    #[track_caller]
+    fn assert_canonical(xml: &str, expected: &str) {
+        let value = Value::from_xml(xml.to_owned()).unwrap();
+        assert_str_eq!(
+            std::str::from_utf8(&value.to_canonical_xml()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn canonical_xml() {
+        // prefixes, whitespace and the order of different elements are
+        // ignored, attributes are sorted
+        assert_canonical(
+            r#"<?xml version="1.0"?>
+            <D:multistatus xmlns:D="DAV:" xmlns:Z="urn:example">
+              <D:response>
+                <D:href>/a</D:href>
+                <D:propstat>
+                  <D:status>HTTP/1.1 200 OK</D:status>
+                  <D:prop><Z:color b="2" xml:lang="en" a='1'>red</Z:color></D:prop>
+                </D:propstat>
+              </D:response>
+              <D:response>
+                <D:href>/b</D:href>
+              </D:response>
+            </D:multistatus>"#,
+            concat!(
+                r#"<ns0:multistatus xmlns:ns0="DAV:">"#,
+                r#"<ns0:response><ns0:href>/a</ns0:href><ns0:propstat>"#,
+                r#"<ns0:prop><ns1:color xmlns:ns1="urn:example" a="1" b="2" xml:lang="en">red</ns1:color></ns0:prop>"#,
+                r#"<ns0:status>HTTP/1.1 200 OK</ns0:status>"#,
+                r#"</ns0:propstat></ns0:response>"#,
+                r#"<ns0:response><ns0:href>/b</ns0:href></ns0:response>"#,
+                r#"</ns0:multistatus>"#,
+            ),
+        );
+        // namespaces of attributes are declared on the element
+        assert_canonical(
+            r#"<a xmlns:x="urn:x" x:b="&quot;&#9;&lt;"> &#13;<c/>&gt; <x:d/></a>"#,
+            r#"<a xmlns:ns0="urn:x" ns0:b="&quot;&#x9;&lt;"> &#xD;<c></c>&gt; <ns0:d></ns0:d></a>"#,
+        );
+        // a namespace is declared again in a sibling, with the same prefix
+        assert_canonical(
+            r#"<r><a:x xmlns:a="urn:a"/><b:y xmlns:b="urn:b"/><a:z xmlns:a="urn:a"/></r>"#,
+            r#"<r><ns0:x xmlns:ns0="urn:a"></ns0:x><ns0:z xmlns:ns0="urn:a"></ns0:z><ns1:y xmlns:ns1="urn:b"></ns1:y></r>"#,
+        );
+    }
+
+    #[test]
+    fn canonicalize() {
+        let name = |local_name| ElementName::new("urn:example", local_name);
+
+        // whitespace and empty maps
+        assert_eq!(Value::from(" \n ").canonicalize(), Value::Empty);
+        assert_eq!(Value::Map(ValueMap::new()).canonicalize(), Value::Empty);
+
+        // mixed content without significant text
+        let mixed = Value::Mixed(vec![
+            Node::Text("\n  ".into()),
+            Node::Element(name("b"), Value::Empty),
+            Node::Text("".into()),
+            Node::Text("\n".into()),
+            Node::Element(name("a"), "1".into()),
+            Node::Element(name("b"), Value::Empty),
+        ]);
+        let mut map = ValueMap::new();
+        map.insert_by_name(name("a"), "1".into());
+        map.insert_by_name(name("b"), Value::Empty);
+        map.insert_by_name(name("b"), Value::Empty);
+        assert_eq!(mixed.canonicalize(), Value::Map(map));
+
+        // adjacent text nodes
+        let mixed = Value::Mixed(vec![
+            Node::Text("a".into()),
+            Node::Text("b".into()),
+            Node::Element(name("c"), Value::Map(ValueMap::new())),
+        ]);
+        assert_eq!(
+            mixed.canonicalize(),
+            Value::Mixed(vec![
+                Node::Text("ab".into()),
+                Node::Element(name("c"), Value::Empty),
+            ])
+        );
+
+        // many adjacent text nodes
+        let mixed = Value::Mixed(vec![Node::Text("x".into()); 100_000]);
+        assert_eq!(
+            mixed.canonicalize(),
+            Value::from("x".repeat(100_000).as_str())
+        );
+
+        // empty attributes
+        let attributed = Value::Attributed(Box::new((Attributes::new(), "a".into())));
+        assert_eq!(attributed.canonicalize(), Value::from("a"));
+    }
+
+    #[test]
+    fn semantically_eq() -> eyre::Result<()> {
+        let a = Value::from_xml(r#"<a:x xmlns:a="urn:a"><a:y xml:lang="en">1</a:y></a:x>"#)?;
+        let b = Value::from_xml(
+            r#"<x xmlns="urn:a">
+                 <y xml:lang="en">1</y>
+               </x>"#,
+        )?;
+        assert!(a.semantically_eq(&b));
+        assert_eq!(a.to_canonical_xml(), b.to_canonical_xml());
+
+        // text, attributes and the order of elements with the same name are
+        // significant
+        for xml in [
+            r#"<a:x xmlns:a="urn:a"><a:y xml:lang="en"> 1</a:y></a:x>"#,
+            r#"<a:x xmlns:a="urn:a"><a:y xml:lang="de">1</a:y></a:x>"#,
+            r#"<a:x xmlns:a="urn:b"><a:y xml:lang="en">1</a:y></a:x>"#,
+        ] {
+            let c = Value::from_xml(xml)?;
+            assert!(!a.semantically_eq(&c));
+            assert_ne!(a.to_canonical_xml(), c.to_canonical_xml());
+        }
+        let a = Value::from_xml("<x><y>1</y><y>2</y></x>")?;
+        let b = Value::from_xml("<x><y>2</y><y>1</y></x>")?;
+        assert!(!a.semantically_eq(&b));
+
+        Ok(())
+    }
+}
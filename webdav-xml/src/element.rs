@@ -28,6 +28,10 @@ pub(crate) trait ElementExt: Element {
 
 impl<T: Element> ElementExt for T {}
 
+/// The name of an element, consisting of its namespace and local name.
+///
+/// The prefix is only used as a hint when writing the element and is ignored
+/// when comparing names.
 #[derive(Clone, Debug)]
 pub struct ElementName<S = &'static str> {
     pub namespace: Option<S>,
@@ -35,6 +39,40 @@ pub struct ElementName<S = &'static str> {
     pub local_name: S,
 }
 
+impl<S> ElementName<S> {
+    /// Create the name of an element in `namespace`.
+    ///
+    /// Without a [prefix](Self::with_prefix), a prefix is generated when
+    /// writing the element.
+    pub fn new(namespace: impl Into<S>, local_name: impl Into<S>) -> Self {
+        Self {
+            namespace: Some(namespace.into()),
+            prefix: None,
+            local_name: local_name.into(),
+        }
+    }
+    /// Create the name of an element without a namespace.
+    pub fn local(local_name: impl Into<S>) -> Self {
+        Self {
+            namespace: None,
+            prefix: None,
+            local_name: local_name.into(),
+        }
+    }
+    /// Set the prefix that is preferred when writing the element.
+    pub fn with_prefix(mut self, prefix: impl Into<S>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+    /// The name of the element `E`.
+    pub fn of<E: Element>() -> Self
+    where
+        S: From<&'static str>,
+    {
+        E::element_name()
+    }
+}
+
 impl indexmap::Equivalent<ElementName<bytestring::ByteString>> for ElementName<&str> {
     fn equivalent(&self, key: &ElementName<bytestring::ByteString>) -> bool {
         self.namespace == key.namespace.as_deref() && self.local_name == &*key.local_name
@@ -54,3 +92,125 @@ impl<S: std::hash::Hash> std::hash::Hash for ElementName<S> {
         (&self.namespace, &self.local_name).hash(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nonempty::{nonempty, NonEmpty};
+
+    use crate::{
+        elements::Href,
+        utils::{test_deserialize, test_serialize},
+        Element, ExtractElementErrorKind, FromValue, FromXml, IntoValue, IntoXml,
+    };
+
+    #[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+    #[xml(
+        namespace = "http://example.com/ns",
+        prefix = "ex",
+        local_name = "share"
+    )]
+    struct Share {
+        kind: ShareKind,
+        #[xml(nonempty)]
+        href: NonEmpty<Href>,
+        #[xml(list)]
+        mirror: Vec<Mirror>,
+        #[xml(optional)]
+        note: Option<Note>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+    #[xml(
+        namespace = "http://example.com/ns",
+        prefix = "ex",
+        local_name = "share-kind"
+    )]
+    enum ShareKind {
+        Public,
+        #[xml(local_name = "by-link")]
+        ByLink,
+        Mirror(Mirror),
+    }
+
+    #[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+    #[xml(namespace = "http://example.com/ns", prefix = "ex")]
+    struct Mirror;
+
+    #[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+    #[xml(namespace = "http://example.com/ns", prefix = "ex")]
+    struct Note {
+        href: Href,
+    }
+
+    #[test]
+    fn derive_struct() -> eyre::Result<()> {
+        let xml = r#"
+<ex:share xmlns:ex="http://example.com/ns" xmlns:d="DAV:">
+  <ex:share-kind>
+    <ex:by-link/>
+  </ex:share-kind>
+  <d:href>/a</d:href>
+  <d:href>/b</d:href>
+  <ex:mirror/>
+  <ex:mirror/>
+</ex:share>
+        "#;
+        let share = Share {
+            kind: ShareKind::ByLink,
+            href: nonempty![Href("/a".parse()?), Href("/b".parse()?)],
+            mirror: vec![Mirror, Mirror],
+            note: None,
+        };
+
+        test_deserialize(&share, xml)?;
+        // the order of namespace declarations isn't stable, so compare the
+        // round trip instead of the serialized string
+        assert_eq!(share, Share::from_xml(share.clone().into_xml()?)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn derive_enum() -> eyre::Result<()> {
+        let xml = r#"
+<ex:share-kind xmlns:ex="http://example.com/ns">
+  <ex:mirror/>
+</ex:share-kind>
+        "#;
+
+        test_deserialize(&ShareKind::Mirror(Mirror), xml)?;
+        test_serialize(xml, ShareKind::Mirror(Mirror))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn derive_errors() {
+        let missing = Share::from_xml(
+            r#"<ex:share xmlns:ex="http://example.com/ns"><ex:share-kind><ex:public/></ex:share-kind></ex:share>"#,
+        );
+        assert!(matches!(
+            missing,
+            Err(crate::Error::ExtractElement(e))
+                if matches!(e.kind, ExtractElementErrorKind::MissingElement("href"))
+        ));
+
+        let none = ShareKind::from_xml(
+            r#"<ex:share-kind xmlns:ex="http://example.com/ns"><ex:other/></ex:share-kind>"#,
+        );
+        assert!(matches!(
+            none,
+            Err(crate::Error::ExtractElement(e))
+                if matches!(e.kind, ExtractElementErrorKind::ExpectedOneOf(["public", "by-link", "mirror"]))
+        ));
+
+        let conflicting = ShareKind::from_xml(
+            r#"<ex:share-kind xmlns:ex="http://example.com/ns"><ex:public/><ex:mirror/></ex:share-kind>"#,
+        );
+        assert!(matches!(
+            conflicting,
+            Err(crate::Error::ExtractElement(e))
+                if matches!(e.kind, ExtractElementErrorKind::ConflictingElements(_))
+        ));
+    }
+}
@@ -1,120 +1,72 @@
+use nonempty::NonEmpty;
+
 use crate::{
-    elements::{Depth, LockRoot, LockScope, LockToken, LockType, Owner},
-    Element, ExtractElementError, ExtractElementErrorKind, Value, ValueMap, DAV_NAMESPACE,
-    DAV_PREFIX,
+    elements::{Depth, Href, LockRoot, LockScope, LockToken, LockType, Owner, Timeout},
+    value::ValueMap,
+    Element, ExtractElementError, FromValue, IntoValue, Value, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `activelock` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_activelock).
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Use [`ActiveLock::try_from_lenient()`] to read locks from servers that
+/// leave out the `lockroot` element.
+#[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct ActiveLock {
     pub lock_scope: LockScope,
     pub lock_type: LockType,
     pub depth: Depth,
+    #[xml(optional)]
     pub owner: Option<Owner>,
+    #[xml(optional)]
+    pub timeout: Option<Timeout>,
+    #[xml(optional)]
     pub lock_token: Option<LockToken>,
     pub lock_root: LockRoot,
 }
 
-impl Element for ActiveLock {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "activelock";
-}
-
-impl TryFrom<&Value> for ActiveLock {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        let lock_scope = match map.get::<LockScope>() {
-            Some(Ok(lock_scope)) => lock_scope,
-            Some(Err(e)) => return Err(e),
-            None => {
-                return Err(ExtractElementError::new(
-                    ExtractElementErrorKind::MissingElement("lockscope"),
-                ))
-            }
-        };
-
-        let lock_type = match map.get::<LockType>() {
-            Some(Ok(lock_type)) => lock_type,
-            Some(Err(e)) => return Err(e),
-            None => {
-                return Err(ExtractElementError::new(
-                    ExtractElementErrorKind::MissingElement("locktype"),
-                ))
-            }
-        };
-
-        let depth = match map.get::<Depth>() {
-            Some(Ok(depth)) => depth,
-            Some(Err(e)) => return Err(e),
-            None => {
-                return Err(ExtractElementError::new(
-                    ExtractElementErrorKind::MissingElement("depth"),
-                ))
-            }
-        };
-
-        let owner = match map.get::<Owner>() {
-            Some(Ok(owner)) => Some(owner),
-            Some(Err(e)) => return Err(e),
+impl ActiveLock {
+    /// Read an `activelock` element without requiring a valid `lockroot`.
+    ///
+    /// The `lockroot` element was introduced in RFC 4918 and is missing in the
+    /// responses of many servers. If it is missing or empty, `lock_root` is
+    /// used instead, typically the URL of the resource the lock was requested
+    /// for. If there are several `lockroot` elements, the first one is used.
+    pub fn try_from_lenient(value: &Value, lock_root: &Href) -> Result<Self, ExtractElementError> {
+        let mut map = value.to_map()?.clone();
+        let lock_root = match map.remove_by_name(Some(DAV_NAMESPACE), LockRoot::LOCAL_NAME) {
+            Some(value) => hrefs(&value)?,
             None => None,
-        };
-
-        let lock_token = match map.get::<LockToken>() {
-            Some(Ok(lock_token)) => Some(lock_token),
-            Some(Err(e)) => return Err(e),
-            None => None,
-        };
-
-        let lock_root = match map.get::<LockRoot>() {
-            Some(Ok(lock_root)) => lock_root,
-            Some(Err(e)) => return Err(e),
-            None => {
-                return Err(ExtractElementError::new(
-                    ExtractElementErrorKind::MissingElement("lockroot"),
-                ))
-            }
-        };
+        }
+        .unwrap_or_else(|| NonEmpty::new(lock_root.clone()));
 
-        Ok(ActiveLock {
-            lock_scope,
-            lock_type,
-            depth,
-            owner,
-            lock_token,
-            lock_root,
-        })
+        map.insert::<LockRoot>(LockRoot { href: lock_root }.into());
+        Self::try_from(&Value::Map(map))
     }
 }
 
-impl From<ActiveLock> for Value {
-    fn from(active_lock: ActiveLock) -> Self {
-        let mut map = ValueMap::new();
-        map.insert::<LockScope>(active_lock.lock_scope.into());
-        map.insert::<LockType>(active_lock.lock_type.into());
-        map.insert::<Depth>(active_lock.depth.into());
-
-        if let Some(owner) = active_lock.owner {
-            map.insert::<Owner>(owner.into());
-        }
-
-        if let Some(lock_token) = active_lock.lock_token {
-            map.insert::<LockToken>(lock_token.into());
-        }
-
-        map.insert::<LockRoot>(active_lock.lock_root.into());
-
-        Value::Map(map)
+/// The `href`s of the first `lockroot` element, if any.
+fn hrefs(lock_root: &Value) -> Result<Option<NonEmpty<Href>>, ExtractElementError> {
+    let lock_root = match lock_root.content() {
+        Value::List(list) => list.first(),
+        value => value,
+    };
+    match lock_root.content() {
+        Value::Empty => Ok(None),
+        value => value
+            .to_map()
+            .map(ValueMap::iter_all)?
+            .collect::<Result<_, _>>()
+            .map(NonEmpty::from_vec),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use nonempty::NonEmpty;
+
     use crate::{
-        elements::{ActiveLock, Depth, Href, LockRoot, LockScope, LockType},
+        elements::{ActiveLock, Depth, Href, LockRoot, LockScope, LockType, Timeout},
         FromXml, IntoXml,
     };
 
@@ -133,6 +85,7 @@ mod tests {
   <d:owner>
     <d:href>http://example.com/user</d:href>
   </d:owner>
+  <d:timeout>Infinite</d:timeout>
   <d:locktoken>
     <d:href>opaquelocktoken:e8d3f4c2-1f4b-4c3a-9f4e-2d3f4c2b1a2b</d:href>
   </d:locktoken>
@@ -147,6 +100,7 @@ mod tests {
         assert_eq!(active_lock.lock_scope, LockScope::Exclusive);
         assert_eq!(active_lock.lock_type, LockType::Write(Default::default()));
         assert_eq!(active_lock.depth, Depth::Infinity);
+        assert_eq!(active_lock.timeout, Some(Timeout::Infinite));
 
         assert!(active_lock.owner.is_some_and(|owner| {
             let href = owner.get::<Href>().flatten().and_then(|h| h.ok()).unwrap();
@@ -155,10 +109,10 @@ mod tests {
         }));
 
         assert!(active_lock.lock_token.is_some_and(|lock_token| {
-            lock_token.href.0 == "opaquelocktoken:e8d3f4c2-1f4b-4c3a-9f4e-2d3f4c2b1a2b"
+            lock_token.href.to_string() == "opaquelocktoken:e8d3f4c2-1f4b-4c3a-9f4e-2d3f4c2b1a2b"
         }));
 
-        assert!(active_lock.lock_root.href.0 == "http://example.com/resource");
+        assert!(active_lock.lock_root.href.head.0 == "http://example.com/resource");
     }
 
     #[test]
@@ -168,9 +122,12 @@ mod tests {
             lock_type: LockType::Write(Default::default()),
             depth: Depth::One,
             owner: None,
+            timeout: Some(Timeout::Seconds(3600)),
             lock_token: None,
             lock_root: LockRoot {
-                href: Href("http://example.com/resource".parse().expect("Invalid URL")),
+                href: NonEmpty::new(Href(
+                    "http://example.com/resource".parse().expect("Invalid URL"),
+                )),
             },
         };
 
@@ -190,6 +147,7 @@ mod tests {
     <d:write/>
   </d:locktype>
   <d:depth>1</d:depth>
+  <d:timeout>Second-3600</d:timeout>
   <d:lockroot>
     <d:href>http://example.com/resource</d:href>
   </d:lockroot>
@@ -199,4 +157,73 @@ mod tests {
 
         assert_eq!(xml, expected_xml);
     }
+
+    #[test]
+    fn test_lenient() -> eyre::Result<()> {
+        use crate::Value;
+
+        let fallback = Href(http::Uri::from_static("/fallback"));
+        let parse = |xml: &str| -> eyre::Result<_> {
+            let value = Value::from_xml(xml.to_owned())?;
+            let value = value
+                .to_map()?
+                .get_by_name(Some("DAV:"), "activelock")
+                .unwrap();
+            Ok((
+                ActiveLock::try_from(value),
+                ActiveLock::try_from_lenient(value, &fallback)?,
+            ))
+        };
+
+        let (strict, lenient) = parse(
+            r#"
+<d:activelock xmlns:d="DAV:">
+  <d:locktype><d:write/></d:locktype>
+  <d:lockscope><d:exclusive/></d:lockscope>
+  <d:depth>0</d:depth>
+  <d:timeout>Second-604800</d:timeout>
+</d:activelock>
+            "#,
+        )?;
+        assert!(strict.is_err());
+        assert_eq!(lenient.lock_root.href, NonEmpty::new(fallback.clone()));
+        assert_eq!(lenient.timeout, Some(Timeout::Seconds(604800)));
+
+        let (strict, lenient) = parse(
+            r#"
+<d:activelock xmlns:d="DAV:">
+  <d:locktype><d:write/></d:locktype>
+  <d:lockscope><d:exclusive/></d:lockscope>
+  <d:depth>0</d:depth>
+  <d:lockroot>
+    <d:href>/a</d:href>
+    <d:href>/b</d:href>
+  </d:lockroot>
+</d:activelock>
+            "#,
+        )?;
+        // all `href`s are kept
+        assert_eq!(strict?, lenient);
+        assert_eq!(
+            lenient.lock_root.href,
+            nonempty::nonempty![
+                Href(http::Uri::from_static("/a")),
+                Href(http::Uri::from_static("/b"))
+            ]
+        );
+
+        let (strict, lenient) = parse(
+            r#"
+<d:activelock xmlns:d="DAV:">
+  <d:locktype><d:write/></d:locktype>
+  <d:lockscope><d:exclusive/></d:lockscope>
+  <d:depth>0</d:depth>
+  <d:lockroot><d:href>/a</d:href></d:lockroot>
+</d:activelock>
+            "#,
+        )?;
+        assert_eq!(strict?, lenient);
+
+        Ok(())
+    }
 }
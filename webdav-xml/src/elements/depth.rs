@@ -4,43 +4,25 @@ use crate::{
 use std::ops::Deref;
 
 /// The `depth` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_depth).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub enum Depth {
     Zero,
     One,
     Infinity,
 }
 
-impl Element for Depth {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "depth";
-}
-
 impl TryFrom<&Value> for Depth {
     type Error = ExtractElementError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        match value {
-            Value::Text(text) => match text.deref() {
-                "0" => Ok(Depth::Zero),
-                "1" => Ok(Depth::One),
-                "infinity" => Ok(Depth::Infinity),
-                _ => Err(ExtractElementError::new(ExtractElementErrorKind::Other(
-                    "depth element must have value of 0, 1, or infinity".into(),
-                ))),
-            },
-            _ => Err(ExtractElementError::new(
-                ExtractElementErrorKind::InvalidValueType {
-                    expected: "text",
-                    got: match value {
-                        Value::Empty => "empty",
-                        Value::Text(_) => "text",
-                        Value::Map(_) => "map",
-                        Value::List(_) => "list",
-                    },
-                },
-            )),
+        match value.to_text()?.deref() {
+            "0" => Ok(Depth::Zero),
+            "1" => Ok(Depth::One),
+            "infinity" => Ok(Depth::Infinity),
+            _ => Err(ExtractElementError::new(ExtractElementErrorKind::Other(
+                "depth element must have value of 0, 1, or infinity".into(),
+            ))),
         }
     }
 }
@@ -0,0 +1,278 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use bytestring::ByteString;
+
+use crate::{
+    elements::Href,
+    value::{Node, Value, ValueMap},
+    Element, ElementName, ExtractElementError, DAV_NAMESPACE, DAV_PREFIX,
+};
+
+/// The `error` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_error).
+///
+/// Contains the precondition and postcondition codes that explain why a
+/// request failed. It is used in [`Response`](crate::elements::Response) and
+/// [`Propstat`](crate::elements::Propstat), or on its own as the body of an
+/// error response:
+///
+/// ```
+/// use webdav_xml::{
+///     elements::{Condition, DavError, Href},
+///     IntoXml,
+/// };
+///
+/// let error = DavError::from(Condition::LockTokenSubmitted(vec![Href(
+///     http::Uri::from_static("/workspace/webdav/"),
+/// )]));
+/// let xml = error.into_xml()?;
+/// # Ok::<(), webdav_xml::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX, local_name = "error")]
+pub struct DavError(pub Vec<Condition>);
+
+/// A precondition or postcondition code as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#precondition.postcondition.xml.elements).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// `lock-token-matches-request-uri`: the lock token in the `Lock-Token`
+    /// header of an `UNLOCK` request doesn't identify a lock on the
+    /// request-URI.
+    LockTokenMatchesRequestUri,
+    /// `lock-token-submitted`: the request could not succeed because a lock
+    /// token should have been submitted for the given URLs.
+    ///
+    /// RFC 4918 requires at least one URL, but uses this condition without
+    /// any URLs in its own examples.
+    LockTokenSubmitted(Vec<Href>),
+    /// `no-conflicting-lock`: a `LOCK` request failed due to an existing
+    /// conflicting lock on the given URLs, which may be empty.
+    NoConflictingLock(Vec<Href>),
+    /// `no-external-entities`: the server doesn't allow external entities in
+    /// the request body.
+    NoExternalEntities,
+    /// `preserved-live-properties`: the server received a valid `MOVE` or
+    /// `COPY` request, but can't maintain the live properties at the
+    /// destination.
+    PreservedLiveProperties,
+    /// `propfind-finite-depth`: the server doesn't allow `PROPFIND` requests
+    /// with `Depth: infinity` on collections.
+    PropfindFiniteDepth,
+    /// `cannot-modify-protected-property`: the client attempted to set a
+    /// protected property in a `PROPPATCH`.
+    CannotModifyProtectedProperty,
+    /// A condition that isn't defined by RFC 4918, e.g. one from another
+    /// namespace like CalDAV's `supported-calendar-data`.
+    Other(ElementName<ByteString>, Value),
+}
+
+impl DavError {
+    pub fn new(condition: Condition) -> Self {
+        Self(vec![condition])
+    }
+}
+
+impl From<Condition> for DavError {
+    fn from(condition: Condition) -> Self {
+        Self::new(condition)
+    }
+}
+
+impl Condition {
+    /// The name of the condition's element.
+    pub fn name(&self) -> ElementName<ByteString> {
+        let local_name = match self {
+            Self::LockTokenMatchesRequestUri => "lock-token-matches-request-uri",
+            Self::LockTokenSubmitted(_) => "lock-token-submitted",
+            Self::NoConflictingLock(_) => "no-conflicting-lock",
+            Self::NoExternalEntities => "no-external-entities",
+            Self::PreservedLiveProperties => "preserved-live-properties",
+            Self::PropfindFiniteDepth => "propfind-finite-depth",
+            Self::CannotModifyProtectedProperty => "cannot-modify-protected-property",
+            Self::Other(name, _) => return name.clone(),
+        };
+        ElementName::new(DAV_NAMESPACE, local_name).with_prefix(DAV_PREFIX)
+    }
+
+    fn from_element(
+        name: &ElementName<ByteString>,
+        value: &Value,
+    ) -> Result<Self, ExtractElementError> {
+        if name.namespace.as_deref() != Some(DAV_NAMESPACE) {
+            return Ok(Self::Other(name.clone(), value.clone()));
+        }
+        Ok(match &*name.local_name {
+            "lock-token-matches-request-uri" => Self::LockTokenMatchesRequestUri,
+            "lock-token-submitted" => Self::LockTokenSubmitted(hrefs(value)?),
+            "no-conflicting-lock" => Self::NoConflictingLock(hrefs(value)?),
+            "no-external-entities" => Self::NoExternalEntities,
+            "preserved-live-properties" => Self::PreservedLiveProperties,
+            "propfind-finite-depth" => Self::PropfindFiniteDepth,
+            "cannot-modify-protected-property" => Self::CannotModifyProtectedProperty,
+            _ => Self::Other(name.clone(), value.clone()),
+        })
+    }
+}
+
+fn hrefs(value: &Value) -> Result<Vec<Href>, ExtractElementError> {
+    match value.is_empty() {
+        true => Ok(Vec::new()),
+        false => value.to_map()?.iter_all().collect(),
+    }
+}
+
+fn hrefs_value(hrefs: Vec<Href>) -> Value {
+    if hrefs.is_empty() {
+        return Value::Empty;
+    }
+    let mut map = ValueMap::new();
+    for href in hrefs {
+        map.insert::<Href>(href.into());
+    }
+    Value::Map(map)
+}
+
+impl TryFrom<&Value> for DavError {
+    type Error = ExtractElementError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let mut conditions = Vec::new();
+        let mut push = |name: &ElementName<ByteString>, value: &Value| {
+            match value {
+                Value::List(list) => {
+                    for value in list.iter() {
+                        conditions.push(Condition::from_element(name, value)?);
+                    }
+                }
+                value => conditions.push(Condition::from_element(name, value)?),
+            }
+            Ok::<_, ExtractElementError>(())
+        };
+
+        match value.content() {
+            // a condition code is required, but some servers only send a
+            // human-readable explanation
+            Value::Empty | Value::Text(_) => {}
+            // text between the conditions, e.g. a human-readable explanation,
+            // is ignored
+            Value::Mixed(nodes) => {
+                for node in nodes {
+                    if let Node::Element(name, value) = node {
+                        push(name, value)?;
+                    }
+                }
+            }
+            _ => {
+                for (name, value) in value.to_map()?.iter() {
+                    push(name, value)?;
+                }
+            }
+        }
+
+        Ok(Self(conditions))
+    }
+}
+
+impl From<DavError> for Value {
+    fn from(DavError(conditions): DavError) -> Value {
+        let mut map = ValueMap::new();
+        for condition in conditions {
+            let name = condition.name();
+            let value = match condition {
+                Condition::LockTokenSubmitted(hrefs) | Condition::NoConflictingLock(hrefs) => {
+                    hrefs_value(hrefs)
+                }
+                Condition::Other(_, value) => value,
+                _ => Value::Empty,
+            };
+            map.insert_by_name(name, value);
+        }
+        Value::Map(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_deserialize, test_serialize};
+
+    #[test]
+    fn lock_token_submitted() -> crate::Result<()> {
+        // http://webdav.org/specs/rfc4918.html#n-example---locked-resource-1
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:error xmlns:d="DAV:">
+  <d:lock-token-submitted>
+    <d:href>/workspace/webdav/</d:href>
+  </d:lock-token-submitted>
+</d:error>
+"#;
+        let error = DavError::new(Condition::LockTokenSubmitted(vec![Href(
+            http::Uri::from_static("/workspace/webdav/"),
+        )]));
+
+        test_deserialize(&error, xml)?;
+        test_serialize(xml, error)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn conditions() -> crate::Result<()> {
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:error xmlns:d="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <d:no-conflicting-lock/>
+  <d:cannot-modify-protected-property/>
+  <C:supported-calendar-data/>
+</d:error>
+"#;
+        let error = DavError(vec![
+            Condition::NoConflictingLock(Vec::new()),
+            Condition::CannotModifyProtectedProperty,
+            Condition::Other(
+                ElementName::new("urn:ietf:params:xml:ns:caldav", "supported-calendar-data")
+                    .with_prefix("C"),
+                Value::Empty,
+            ),
+        ]);
+
+        test_deserialize(&error, xml)?;
+        test_serialize(xml, error)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn all_conditions() -> crate::Result<()> {
+        use crate::{FromXml, IntoXml};
+
+        let error = DavError(vec![
+            Condition::LockTokenMatchesRequestUri,
+            Condition::LockTokenSubmitted(vec![
+                Href(http::Uri::from_static("/a")),
+                Href(http::Uri::from_static("/b")),
+            ]),
+            Condition::NoConflictingLock(vec![Href(http::Uri::from_static("/c"))]),
+            Condition::NoExternalEntities,
+            Condition::PreservedLiveProperties,
+            Condition::PropfindFiniteDepth,
+            Condition::CannotModifyProtectedProperty,
+        ]);
+        assert_eq!(DavError::from_xml(error.clone().into_xml()?)?, error);
+
+        Ok(())
+    }
+
+    #[test]
+    fn explanation() -> crate::Result<()> {
+        let xml = r#"<d:error xmlns:d="DAV:">Depth: infinity is not supported <d:propfind-finite-depth/></d:error>"#;
+        test_deserialize(&DavError::new(Condition::PropfindFiniteDepth), xml)?;
+
+        let xml = r#"<d:error xmlns:d="DAV:"><d:lock-token-submitted>/a</d:lock-token-submitted></d:error>"#;
+        assert!(<DavError as crate::FromXml>::from_xml(xml).is_err());
+
+        Ok(())
+    }
+}
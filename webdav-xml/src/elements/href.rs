@@ -7,15 +7,10 @@ use std::str::FromStr;
 use crate::{value::Value, Element, ExtractElementError, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `href` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_href).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Href(pub http::Uri);
 
-impl Element for Href {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "href";
-}
-
 impl TryFrom<&Value> for Href {
     type Error = ExtractElementError;
 
@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{elements::Href, Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX};
+
+/// The `location` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_location).
+///
+/// Used in a [`Response`](crate::elements::Response) with a 3xx status to
+/// report the new location of a resource.
+#[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
+pub struct Location {
+    pub href: Href,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_deserialize, test_serialize};
+
+    #[test]
+    fn test_location() -> crate::Result<()> {
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:location xmlns:d="DAV:">
+  <d:href>http://www.example.com/loc2/</d:href>
+</d:location>
+"#;
+        let location = Location {
+            href: Href(http::Uri::from_static("http://www.example.com/loc2/")),
+        };
+
+        test_deserialize(&location, xml)?;
+        test_serialize(xml, location)?;
+
+        Ok(())
+    }
+}
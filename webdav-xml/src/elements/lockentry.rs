@@ -1,55 +1,16 @@
 use crate::{
     elements::{LockScope, LockType},
-    Element, ExtractElementError, ExtractElementErrorKind, Value, ValueMap, DAV_NAMESPACE,
-    DAV_PREFIX,
+    Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `lockentry` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_lockentry).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct LockEntry {
     pub lock_scope: LockScope,
     pub lock_type: LockType,
 }
 
-impl Element for LockEntry {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "lockentry";
-}
-
-impl TryFrom<&Value> for LockEntry {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        match (map.get::<LockScope>(), map.get::<LockType>()) {
-            (Some(Ok(lock_scope)), Some(Ok(lock_type))) => Ok(LockEntry {
-                lock_scope,
-                lock_type,
-            }),
-            (Some(Err(e)), _) => Err(e),
-            (_, Some(Err(e))) => Err(e),
-            (None, _) => Err(ExtractElementError::new(
-                ExtractElementErrorKind::MissingElement("lockscope"),
-            )),
-            (_, None) => Err(ExtractElementError::new(
-                ExtractElementErrorKind::MissingElement("locktype"),
-            )),
-        }
-    }
-}
-
-impl From<LockEntry> for Value {
-    fn from(lock_entry: LockEntry) -> Self {
-        let mut map = ValueMap::new();
-        map.insert::<LockScope>(lock_entry.lock_scope.into());
-        map.insert::<LockType>(lock_entry.lock_type.into());
-
-        Value::Map(map)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{
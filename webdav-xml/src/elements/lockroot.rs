@@ -1,47 +1,22 @@
-use crate::{
-    elements::Href, Element, ExtractElementError, ExtractElementErrorKind, Value, ValueMap,
-    DAV_NAMESPACE, DAV_PREFIX,
-};
+use nonempty::NonEmpty;
+
+use crate::{elements::Href, Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `lockroot` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_lockroot).
-#[derive(Clone, Debug, PartialEq)]
+///
+/// RFC 4918 allows a single `href`, but some servers report several, which
+/// are all kept.
+#[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct LockRoot {
-    pub href: Href,
-}
-
-impl Element for LockRoot {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "lockroot";
-}
-
-impl TryFrom<&Value> for LockRoot {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        match map.get::<Href>() {
-            Some(Ok(href)) => Ok(LockRoot { href }),
-            Some(Err(e)) => Err(e),
-            None => Err(ExtractElementError::new(
-                ExtractElementErrorKind::MissingElement("href"),
-            )),
-        }
-    }
-}
-
-impl From<LockRoot> for Value {
-    fn from(lock_root: LockRoot) -> Self {
-        let mut map = ValueMap::new();
-        map.insert::<Href>(lock_root.href.into());
-
-        Value::Map(map)
-    }
+    #[xml(nonempty)]
+    pub href: NonEmpty<Href>,
 }
 
 #[cfg(test)]
 mod tests {
+    use nonempty::NonEmpty;
+
     use crate::{
         elements::{Href, LockRoot},
         FromXml,
@@ -60,7 +35,24 @@ mod tests {
 
         assert_eq!(
             lock_root.href,
-            Href("/path/to/resource".parse().expect("Failed to parse Href"))
+            NonEmpty::new(Href(
+                "/path/to/resource".parse().expect("Failed to parse Href")
+            ))
+        );
+
+        let xml = r#"
+<d:lockroot xmlns:d="DAV:">
+  <d:href>/a</d:href>
+  <d:href>/b</d:href>
+</d:lockroot>
+        "#;
+        let lock_root = LockRoot::from_xml(xml).expect("Failed to deserialize LockRoot");
+        assert_eq!(
+            lock_root.href,
+            nonempty::nonempty![
+                Href(http::Uri::from_static("/a")),
+                Href(http::Uri::from_static("/b"))
+            ]
         );
     }
 }
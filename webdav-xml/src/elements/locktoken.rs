@@ -1,70 +1,72 @@
 use crate::{
-    elements::Href, Element, ExtractElementError, ExtractElementErrorKind, Value, ValueMap,
+    elements::Href, value::ValueMap, Element, ElementName, ExtractElementError, OptionExt, Value,
     DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `locktoken` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_locktoken).
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Lock tokens are absolute URIs like `urn:uuid:…`, which can't be
+/// represented by [`Href`], so the content of its `href` is stored as an
+/// [`AbsoluteUri`](uniresid::AbsoluteUri).
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct LockToken {
-    pub href: Href,
-}
-
-impl Element for LockToken {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "locktoken";
+    pub href: uniresid::AbsoluteUri,
 }
 
 impl TryFrom<&Value> for LockToken {
     type Error = ExtractElementError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        match map.get::<Href>() {
-            Some(Ok(href)) => Ok(LockToken { href }),
-            Some(Err(e)) => Err(e),
-            None => Err(ExtractElementError::new(
-                ExtractElementErrorKind::MissingElement("href"),
-            )),
-        }
+        let href = value
+            .to_map()?
+            .get_by_name(Some(DAV_NAMESPACE), Href::LOCAL_NAME)
+            .required::<Href>()?;
+        href.to_text()
+            .and_then(|href| uniresid::AbsoluteUri::parse(href).map_err(ExtractElementError::other))
+            .map(|href| Self { href })
+            .map_err(|e| e.within(ElementName::of::<Href>(), None))
     }
 }
 
 impl From<LockToken> for Value {
-    fn from(lock_token: LockToken) -> Self {
+    fn from(LockToken { href }: LockToken) -> Value {
         let mut map = ValueMap::new();
-        map.insert::<Href>(lock_token.href.into());
-
+        map.insert::<Href>(Value::Text(href.to_string().into()));
         Value::Map(map)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        elements::{Href, LockToken},
-        FromXml, IntoXml,
-    };
+    use crate::{elements::LockToken, FromXml, IntoXml};
 
     #[test]
     fn test_deserialize() {
         let xml = r#"
 <?xml version="1.0" encoding="utf-8"?>
 <d:locktoken xmlns:d="DAV:">
-  <d:href>/path/to/locktoken</d:href>
+  <d:href>urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76</d:href>
 </d:locktoken>
         "#;
 
         let lock_token = LockToken::from_xml(xml).expect("Failed to deserialize LockToken");
 
-        assert_eq!(lock_token.href.0, "/path/to/locktoken");
+        assert_eq!(
+            lock_token.href.to_string(),
+            "urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76"
+        );
+
+        // lock tokens are absolute URIs
+        let xml = xml.replace("urn:uuid:", "/locks/");
+        assert!(LockToken::from_xml(xml).is_err());
     }
 
     #[test]
     fn test_serialize() {
         let lock_token = LockToken {
-            href: Href("/path/to/locktoken".parse().expect("Failed to parse Href")),
+            href: uniresid::AbsoluteUri::parse("urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76")
+                .expect("Failed to parse AbsoluteUri"),
         };
 
         let bytes = lock_token
@@ -76,7 +78,7 @@ mod tests {
         let expected_xml = r#"
 <?xml version="1.0" encoding="utf-8"?>
 <d:locktoken xmlns:d="DAV:">
-  <d:href>/path/to/locktoken</d:href>
+  <d:href>urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76</d:href>
 </d:locktoken>
         "#
         .trim();
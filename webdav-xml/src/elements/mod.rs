@@ -7,7 +7,9 @@
 
 mod activelock;
 mod depth;
+mod error;
 mod href;
+mod location;
 mod lockentry;
 mod lockinfo;
 mod lockroot;
@@ -28,7 +30,9 @@ mod timeout;
 pub use self::{
     activelock::ActiveLock,
     depth::Depth,
+    error::{Condition, DavError},
     href::Href,
+    location::Location,
     lockentry::LockEntry,
     lockinfo::LockInfo,
     lockroot::LockRoot,
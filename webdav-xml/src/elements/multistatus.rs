@@ -2,63 +2,21 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use nonempty::NonEmpty;
-
 use crate::{
     elements::{response::Response, ResponseDescription},
-    value::ValueMap,
-    Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX,
+    Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `multistatus` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_multistatus).
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Multistatus {
+    #[xml(list)]
     pub response: Vec<Response>,
+    #[xml(optional)]
     pub responsedescription: Option<ResponseDescription>,
 }
 
-impl Element for Multistatus {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "multistatus";
-}
-
-impl TryFrom<&Value> for Multistatus {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        Ok(Multistatus {
-            response: map.iter_all().collect::<Result<_, _>>()?,
-            responsedescription: map.get().transpose()?,
-        })
-    }
-}
-
-impl From<Multistatus> for Value {
-    fn from(
-        Multistatus {
-            response,
-            responsedescription,
-        }: Multistatus,
-    ) -> Value {
-        let mut map = ValueMap::new();
-
-        map.insert::<Response>(
-            match NonEmpty::collect(response.into_iter().map(Value::from)) {
-                Some(responses) => Value::List(Box::new(responses)),
-                None => Value::Empty,
-            },
-        );
-        if let Some(responsedescription) = responsedescription {
-            map.insert::<ResponseDescription>(responsedescription.into())
-        }
-
-        Value::Map(map)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::FromXml as _;
@@ -117,6 +75,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_error_example() -> eyre::Result<()> {
+        use crate::elements::{Condition, DavError, Href, Status};
+
+        // http://webdav.org/specs/rfc4918.html#n-example---copy-with-depth-infinity
+        let xml = r#"
+        <?xml version="1.0" encoding="utf-8" ?>
+        <d:multistatus xmlns:d="DAV:">
+          <d:response>
+            <d:href>http://www.example.com/othercontainer/C2/</d:href>
+            <d:status>HTTP/1.1 423 Locked</d:status>
+            <d:error><d:lock-token-submitted/></d:error>
+          </d:response>
+        </d:multistatus>
+        "#;
+        let multistatus = Multistatus::from_xml(xml)?;
+
+        assert_eq!(
+            multistatus.response,
+            [Response::Status {
+                href: nonempty::nonempty![Href(http::Uri::from_static(
+                    "http://www.example.com/othercontainer/C2/"
+                ))],
+                status: Status(http::StatusCode::LOCKED),
+                error: Some(DavError::new(Condition::LockTokenSubmitted(Vec::new()))),
+                responsedescription: None,
+                location: None,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_location_example() -> eyre::Result<()> {
+        use crate::{
+            elements::{Href, Location, Status},
+            IntoXml,
+        };
+
+        // http://webdav.org/specs/rfc4918.html#ELEMENT_location
+        let xml = r#"
+        <?xml version="1.0" encoding="utf-8" ?>
+        <d:multistatus xmlns:d="DAV:">
+          <d:response>
+            <d:href>http://www.example.com/loc2</d:href>
+            <d:status>HTTP/1.1 301 Moved Permanently</d:status>
+            <d:location>
+              <d:href>http://www.example.com/loc2/</d:href>
+            </d:location>
+          </d:response>
+        </d:multistatus>
+        "#;
+        let multistatus = Multistatus::from_xml(xml)?;
+
+        let response = Response::Status {
+            href: nonempty::nonempty![Href(http::Uri::from_static("http://www.example.com/loc2"))],
+            status: Status(http::StatusCode::MOVED_PERMANENTLY),
+            error: None,
+            responsedescription: None,
+            location: Some(Location {
+                href: Href(http::Uri::from_static("http://www.example.com/loc2/")),
+            }),
+        };
+        assert_eq!(multistatus.response, std::slice::from_ref(&response));
+
+        let roundtrip = Multistatus::from_xml(
+            Multistatus {
+                response: vec![response],
+                responsedescription: None,
+            }
+            .into_xml()?,
+        )?;
+        assert_eq!(roundtrip, multistatus);
+
+        Ok(())
+    }
+
     #[test]
     fn parse_multiple_responses() -> eyre::Result<()> {
         let response = r#"
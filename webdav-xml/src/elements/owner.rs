@@ -1,33 +1,73 @@
 use crate::{
-    element::ElementName, Element, ExtractElementError, Value, ValueMap, DAV_NAMESPACE, DAV_PREFIX,
+    element::{ElementExt, ElementName},
+    value::Node,
+    Element, ExtractElementError, Value, ValueMap, DAV_NAMESPACE, DAV_PREFIX,
 };
 use bytestring::ByteString;
 
 /// The `owner` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_owner).
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct Owner(pub ValueMap);
+///
+/// The content of `owner` is arbitrary XML, so it is kept as a [`Value`],
+/// which may be text, child elements or [mixed content](Value::Mixed).
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
+pub struct Owner(pub Value);
 
 impl Owner {
     pub fn new() -> Self {
         Self::default()
     }
-    pub fn with<E>(mut self, e: E) -> Self
+    pub fn with<E>(self, e: E) -> Self
     where
         E: Element + Into<Value>,
     {
-        self.0.insert::<E>(e.into());
-        self
+        self.push::<E>(e.into())
     }
-    pub fn with_name<E>(mut self) -> Self
+    pub fn with_name<E>(self) -> Self
     where
         E: Element,
     {
-        self.0.insert::<E>(Value::Empty);
-        self
+        self.push::<E>(Value::Empty)
+    }
+    fn push<E>(self, value: Value) -> Self
+    where
+        E: Element,
+    {
+        let content = match self.0 {
+            Value::Map(mut map) => {
+                map.insert::<E>(value);
+                Value::Map(map)
+            }
+            Value::Mixed(mut nodes) => {
+                nodes.push(Node::Element(E::element_name(), value));
+                Value::Mixed(nodes)
+            }
+            Value::Text(text) => Value::Mixed(vec![
+                Node::Text(text),
+                Node::Element(E::element_name(), value),
+            ]),
+            _ => {
+                let mut map = ValueMap::new();
+                map.insert::<E>(value);
+                Value::Map(map)
+            }
+        };
+        Self(content)
     }
     /// List the names of the properties in this `owner` element.
     pub fn names(&self) -> impl Iterator<Item = &ElementName<ByteString>> {
-        self.0 .0.keys()
+        let (map, nodes) = match &self.0 {
+            Value::Map(map) => (Some(map), None),
+            Value::Mixed(nodes) => (None, Some(nodes)),
+            _ => (None, None),
+        };
+        map.into_iter()
+            .flat_map(|map| map.0.keys())
+            .chain(nodes.into_iter().flatten().filter_map(Node::name))
+    }
+    /// The text content of this `owner` element, if it only contains text.
+    pub fn text(&self) -> Option<&ByteString> {
+        self.0.to_text().ok()
     }
 }
 
@@ -44,27 +84,30 @@ impl Owner {
     where
         P: Element + TryFrom<&'v Value, Error = ExtractElementError>,
     {
-        self.0.get_optional()
+        match &self.0 {
+            Value::Map(map) => map.get_optional(),
+            Value::Mixed(nodes) => nodes.iter().find_map(|node| match node {
+                Node::Element(name, value) if *name == P::element_name() => {
+                    Some((!value.is_empty()).then(|| P::try_from(value)))
+                }
+                _ => None,
+            }),
+            _ => None,
+        }
     }
 }
 
-impl Element for Owner {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "owner";
-}
-
 impl TryFrom<&Value> for Owner {
     type Error = ExtractElementError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        value.to_map().cloned().map(Self)
+        Ok(Self(value.clone()))
     }
 }
 
 impl From<Owner> for Value {
-    fn from(Owner(map): Owner) -> Self {
-        Value::Map(map)
+    fn from(Owner(value): Owner) -> Self {
+        value
     }
 }
 
@@ -72,7 +115,7 @@ impl From<Owner> for Value {
 mod tests {
     use crate::{
         elements::{Href, Owner},
-        FromXml, IntoXml, ValueMap,
+        FromXml, IntoXml, Value, ValueMap,
     };
 
     #[test]
@@ -99,7 +142,7 @@ mod tests {
         owner_values
             .insert::<Href>(Href("http://example.com/user1".parse().expect("Invalid URI")).into());
 
-        let owner = Owner(owner_values);
+        let owner = Owner(Value::Map(owner_values));
 
         let bytes = owner.into_xml().expect("Failed to serialize Owner");
         let xml = String::from_utf8(bytes.to_vec()).expect("Failed to convert bytes to string");
@@ -113,4 +156,33 @@ mod tests {
 
         assert_eq!(xml.trim(), expected_xml.trim());
     }
+
+    #[test]
+    fn test_mixed_content() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:owner xmlns:d="DAV:">Jane <d:href>mailto:jane@example.com</d:href></d:owner>"#;
+
+        let owner = Owner::from_xml(xml).expect("Failed to deserialize Owner");
+
+        assert!(owner.0.is_mixed());
+        assert!(owner
+            .get::<Href>()
+            .flatten()
+            .and_then(|h| h.ok())
+            .is_some_and(|h| h.0 == "mailto:jane@example.com"));
+        assert_eq!(owner.names().count(), 1);
+
+        let bytes = owner.into_xml().expect("Failed to serialize Owner");
+        assert_eq!(std::str::from_utf8(&bytes).unwrap(), xml);
+    }
+
+    #[test]
+    fn test_text() {
+        let owner =
+            Owner::from_xml(r#"<d:owner xmlns:d="DAV:"><![CDATA[<Jane>]]> &amp; John</d:owner>"#)
+                .expect("Failed to deserialize Owner");
+
+        assert_eq!(owner.0, Value::Text("<Jane> & John".into()));
+        assert_eq!(owner.text().map(|t| &**t), Some("<Jane> & John"));
+    }
 }
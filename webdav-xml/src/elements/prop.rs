@@ -5,20 +5,22 @@
 use bytestring::ByteString;
 
 use crate::{
-    element::{Element, ElementName},
+    element::ElementName,
+    elements::Href,
     properties::{
-        ContentLanguage, ContentLength, ContentType, CreationDate, DisplayName, ETag, LastModified,
-        LockDiscovery, ResourceType, SupportedLock,
+        ContentLanguage, ContentLength, ContentType, CreationDate, DisplayName, ETag,
+        KnownProperty, LastModified, LockDiscovery, PropertyRegistry, ResourceType, SupportedLock,
     },
     value::{Value, ValueMap},
-    ExtractElementError, DAV_NAMESPACE, DAV_PREFIX,
+    Attributes, Element, ExtractElementError, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `prop` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_prop).
 ///
 /// This element can contain arbitrary child elements and supports extracting
 /// them using [`Properties::get()`].
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX, local_name = "prop")]
 pub struct Properties(ValueMap);
 
 impl Properties {
@@ -39,6 +41,45 @@ impl Properties {
         self.0.insert::<E>(Value::Empty);
         self
     }
+    /// Add a property with a name that is only known at runtime, e.g. a dead
+    /// property.
+    ///
+    /// ```
+    /// use webdav_xml::{elements::Properties, ElementName, Value};
+    ///
+    /// let prop = Properties::new().with_by_name(
+    ///     ElementName::new("http://example.com/ns", "color").with_prefix("ex"),
+    ///     "red",
+    /// );
+    /// assert_eq!(
+    ///     prop.get_by_name(Some("http://example.com/ns"), "color"),
+    ///     Some(&Value::Text("red".into()))
+    /// );
+    /// ```
+    pub fn with_by_name(mut self, name: ElementName<ByteString>, value: impl Into<Value>) -> Self {
+        self.insert_by_name(name, value);
+        self
+    }
+    /// Add a property with a name that is only known at runtime.
+    ///
+    /// See [`ValueMap::insert_by_name()`].
+    pub fn insert_by_name(&mut self, name: ElementName<ByteString>, value: impl Into<Value>) {
+        self.0.insert_by_name(name, value.into())
+    }
+    /// Remove a property by its namespace and local name.
+    pub fn remove_by_name(&mut self, namespace: Option<&str>, local_name: &str) -> Option<Value> {
+        self.0.remove_by_name(namespace, local_name)
+    }
+    /// Add a property with an `xml:lang` attribute.
+    pub fn with_lang<E>(mut self, e: E, lang: impl Into<ByteString>) -> Self
+    where
+        E: Element + Into<Value>,
+    {
+        let mut attributes = Attributes::new();
+        attributes.set_lang(lang);
+        self.0.insert::<E>(e.into().with_attributes(attributes));
+        self
+    }
 }
 
 impl Properties {
@@ -56,10 +97,78 @@ impl Properties {
     {
         self.0.get_optional()
     }
+    /// Read the attributes of a specific property.
+    pub fn attributes<P: Element>(&self) -> Option<&Attributes> {
+        self.0.attributes::<P>()
+    }
+    /// Read the language of a specific property from its `xml:lang`
+    /// attribute.
+    ///
+    /// Only the attribute of the property element itself is considered, not
+    /// one that is inherited from an ancestor.
+    pub fn lang<P: Element>(&self) -> Option<&str> {
+        self.attributes::<P>()?.lang().map(|lang| &**lang)
+    }
+    /// Read the raw value of a property by its namespace and local name.
+    pub fn get_by_name(&self, namespace: Option<&str>, local_name: &str) -> Option<&Value> {
+        self.0.get_by_name(namespace, local_name)
+    }
+    /// Iterate over the names and raw values of all properties.
+    pub fn iter(&self) -> impl Iterator<Item = (&ElementName<ByteString>, &Value)> {
+        self.0.iter()
+    }
     /// List the names of the properties in this `prop` element.
     pub fn names(&self) -> impl Iterator<Item = &ElementName<ByteString>> {
         self.0 .0.keys()
     }
+    /// Iterate over all properties, extracted as the types in `registry`.
+    ///
+    /// Empty properties are returned as [`KnownProperty::Empty`] and
+    /// properties that aren't in the registry as [`KnownProperty::Unknown`].
+    pub fn iter_typed<'a, C>(
+        &'a self,
+        registry: &'a PropertyRegistry<C>,
+    ) -> impl Iterator<Item = Result<KnownProperty<C>, ExtractElementError>> + 'a {
+        self.iter()
+            .map(|(name, value)| registry.decode(name, value))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl Properties {
+    /// Read a property by its namespace and local name using its
+    /// [`Deserialize`](::serde::Deserialize) implementation.
+    ///
+    /// Returns `None` if the property doesn't exist. See [`crate::serde`] for
+    /// how XML is mapped to Rust types.
+    pub fn get_serde<'v, T>(
+        &'v self,
+        namespace: Option<&str>,
+        local_name: &str,
+    ) -> Option<Result<T, ExtractElementError>>
+    where
+        T: ::serde::Deserialize<'v>,
+    {
+        self.get_by_name(namespace, local_name)
+            .map(crate::serde::from_value)
+    }
+    /// Add a property using its [`Serialize`](::serde::Serialize)
+    /// implementation.
+    ///
+    /// Child elements are put into the namespace of the property.
+    pub fn with_serde<T>(
+        mut self,
+        name: ElementName<ByteString>,
+        value: &T,
+    ) -> Result<Self, crate::serde::SerializeError>
+    where
+        T: ::serde::Serialize + ?Sized,
+    {
+        let value = value.serialize(crate::serde::Serializer::for_element(&name))?;
+        self.insert_by_name(name, value);
+        Ok(self)
+    }
 }
 
 impl Properties {
@@ -109,10 +218,30 @@ impl Properties {
     }
     /// Read the `lockdiscovery` property.
     ///
-    /// See [`Properties::get()`] for an overview of the possible return values.
+    /// See [`Properties::get()`] for an overview of the possible return values
+    /// and [`Properties::lockdiscovery_lenient()`] for servers that leave out
+    /// the `lockroot` of their locks.
     pub fn lockdiscovery(&self) -> Option<Option<Result<LockDiscovery, ExtractElementError>>> {
         self.get()
     }
+    /// Read the `lockdiscovery` property with
+    /// [`LockDiscovery::try_from_lenient()`], using `lock_root` for locks
+    /// without a `lockroot`.
+    ///
+    /// See [`Properties::get()`] for an overview of the possible return values.
+    pub fn lockdiscovery_lenient(
+        &self,
+        lock_root: &Href,
+    ) -> Option<Option<Result<LockDiscovery, ExtractElementError>>> {
+        self.get_by_name(Some(DAV_NAMESPACE), LockDiscovery::LOCAL_NAME)
+            .map(|value| match value.is_empty() {
+                true => None,
+                false => Some(
+                    LockDiscovery::try_from_lenient(value, lock_root)
+                        .map_err(|e| e.within(ElementName::of::<LockDiscovery>(), None)),
+                ),
+            })
+    }
     /// Read the `resourcetype` property.
     ///
     /// See [`Properties::get()`] for an overview of the possible return values.
@@ -127,12 +256,6 @@ impl Properties {
     }
 }
 
-impl Element for Properties {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "prop";
-}
-
 impl TryFrom<&Value> for Properties {
     type Error = ExtractElementError;
 
@@ -153,6 +276,7 @@ mod tests {
     use crate::properties::*;
     use crate::FromXml;
     use crate::IntoXml;
+    use crate::{ElementName, Value};
 
     #[test]
     fn test_deserialize() -> eyre::Result<()> {
@@ -218,4 +342,131 @@ mod tests {
 
         assert_eq!(xml.trim(), expected_xml.trim());
     }
+
+    #[test]
+    fn test_lockdiscovery_lenient() -> eyre::Result<()> {
+        use crate::elements::Href;
+
+        let xml = r#"
+<d:prop xmlns:d="DAV:">
+  <d:lockdiscovery>
+    <d:activelock>
+      <d:locktype><d:write/></d:locktype>
+      <d:lockscope><d:exclusive/></d:lockscope>
+      <d:depth>0</d:depth>
+    </d:activelock>
+  </d:lockdiscovery>
+</d:prop>
+"#;
+        let prop = Properties::from_xml(xml)?;
+        let lock_root = Href(http::Uri::from_static("/container/"));
+
+        assert!(prop.lockdiscovery().unwrap().unwrap().is_err());
+        let lockdiscovery = prop.lockdiscovery_lenient(&lock_root).unwrap().unwrap()?;
+        assert_eq!(lockdiscovery.0.len(), 1);
+        assert_eq!(lockdiscovery.0[0].lock_root.href.head, lock_root);
+
+        let prop = Properties::new().with_name::<LockDiscovery>();
+        assert!(prop.lockdiscovery_lenient(&lock_root).unwrap().is_none());
+        assert!(Properties::new()
+            .lockdiscovery_lenient(&lock_root)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lang() -> eyre::Result<()> {
+        use pretty_assertions::assert_eq;
+
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:">
+  <d:displayname xml:lang="de-DE">Beispiel</d:displayname>
+  <d:getcontentlength>0</d:getcontentlength>
+</d:prop>
+"#;
+
+        let prop = Properties::from_xml(xml)?;
+
+        assert_eq!(prop.lang::<DisplayName>(), Some("de-DE"));
+        assert_eq!(prop.lang::<ContentLength>(), None);
+        assert_eq!(
+            prop.displayname().unwrap().unwrap()?,
+            DisplayName("Beispiel".into())
+        );
+
+        let expected = Properties::new()
+            .with_lang(DisplayName("Beispiel".into()), "de-DE")
+            .with(ContentLength(0));
+        assert_eq!(prop, expected);
+
+        let bytes = expected.into_xml()?;
+        assert_eq!(std::str::from_utf8(&bytes)?.trim(), xml.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dead_property() -> eyre::Result<()> {
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:">
+  <d:displayname>Example</d:displayname>
+  <d:owner>Jane <d:href>mailto:jane@example.com</d:href> (<![CDATA[<admin>]]>)</d:owner>
+</d:prop>
+"#;
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:">
+  <d:displayname>Example</d:displayname>
+  <d:owner>Jane <d:href>mailto:jane@example.com</d:href> (&lt;admin&gt;)</d:owner>
+</d:prop>
+"#;
+
+        let prop = Properties::from_xml(xml)?;
+        let bytes = prop.clone().into_xml()?;
+        assert_eq!(std::str::from_utf8(&bytes)?.trim(), expected.trim());
+        assert_eq!(Properties::from_xml(bytes)?, prop);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_by_name() -> eyre::Result<()> {
+        use pretty_assertions::assert_eq;
+
+        let typed = Properties::new()
+            .with(DisplayName("Example".into()))
+            .with(ContentLength(0));
+        let mut dynamic = Properties::new()
+            .with_by_name(ElementName::new("DAV:", "displayname"), "Example")
+            .with_by_name(
+                ElementName::new("DAV:", "getcontentlength").with_prefix("d"),
+                "0",
+            )
+            .with_by_name(ElementName::new("urn:example", "color"), "red");
+
+        assert_eq!(
+            dynamic.get_by_name(Some("urn:example"), "color"),
+            Some(&Value::Text("red".into()))
+        );
+        assert_eq!(
+            dynamic.remove_by_name(Some("urn:example"), "color"),
+            Some(Value::Text("red".into()))
+        );
+        assert_eq!(dynamic.get_by_name(Some("urn:example"), "color"), None);
+        assert_eq!(
+            dynamic
+                .iter()
+                .map(|(name, _)| &*name.local_name)
+                .collect::<Vec<_>>(),
+            ["displayname", "getcontentlength"]
+        );
+
+        assert_eq!(dynamic, typed);
+        assert_eq!(dynamic.into_xml()?, typed.into_xml()?);
+
+        Ok(())
+    }
 }
@@ -1,137 +1,30 @@
-use crate::{
-    elements::Properties, Element, ExtractElementError, Value, ValueMap, DAV_NAMESPACE, DAV_PREFIX,
-};
+use crate::{elements::Properties, Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `propertyupdate` XML element as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_propertyupdate).
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct PropertyUpdate {
+    #[xml(optional)]
     pub set: Option<Set>,
+    #[xml(optional)]
     pub remove: Option<Remove>,
 }
 
-impl Element for PropertyUpdate {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "propertyupdate";
-}
-
-impl TryFrom<&Value> for PropertyUpdate {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        let set = match map.get::<Set>() {
-            Some(set_value) => Some(set_value?),
-            None => None,
-        };
-
-        let remove = match map.get::<Remove>() {
-            Some(remove_value) => Some(remove_value?),
-            None => None,
-        };
-
-        Ok(PropertyUpdate { set, remove })
-    }
-}
-
-impl From<PropertyUpdate> for Value {
-    fn from(property_update: PropertyUpdate) -> Value {
-        let mut map = ValueMap::new();
-
-        if let Some(set) = property_update.set {
-            map.insert::<Set>(set.into());
-        }
-
-        if let Some(remove) = property_update.remove {
-            map.insert::<Remove>(remove.into());
-        }
-
-        Value::Map(map)
-    }
-}
-
 /// The `remove` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_remove).
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Remove {
     pub properties: Properties,
 }
 
-impl Element for Remove {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "remove";
-}
-
-impl TryFrom<&Value> for Remove {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        let properties = match map.get::<Properties>() {
-            Some(prop_value) => prop_value?,
-            None => {
-                return Err(ExtractElementError::new(
-                    crate::ExtractElementErrorKind::MissingElement("prop"),
-                ))
-            }
-        };
-
-        Ok(Remove { properties })
-    }
-}
-
-impl From<Remove> for Value {
-    fn from(remove: Remove) -> Value {
-        let mut map = ValueMap::new();
-        map.insert::<Properties>(remove.properties.into());
-
-        Value::Map(map)
-    }
-}
-
 /// The `set` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_set).
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Set {
     pub properties: Properties,
 }
 
-impl Element for Set {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "set";
-}
-
-impl TryFrom<&Value> for Set {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-
-        let properties = match map.get::<Properties>() {
-            Some(prop_value) => prop_value?,
-            None => {
-                return Err(ExtractElementError::new(
-                    crate::ExtractElementErrorKind::MissingElement("prop"),
-                ))
-            }
-        };
-
-        Ok(Set { properties })
-    }
-}
-
-impl From<Set> for Value {
-    fn from(set: Set) -> Value {
-        let mut map = ValueMap::new();
-        map.insert::<Properties>(set.properties.into());
-
-        Value::Map(map)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::time::SystemTime;
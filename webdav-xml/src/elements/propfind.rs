@@ -5,24 +5,19 @@
 use bytestring::ByteString;
 
 use crate::{
-    elements::Properties, Element, ExtractElementError, ExtractElementErrorKind, Value, ValueMap,
-    DAV_NAMESPACE, DAV_PREFIX,
+    elements::Properties, Element, ElementName, ExtractElementError, ExtractElementErrorKind,
+    FromValue, IntoValue, Value, ValueMap, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `propfind` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_propfind).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub enum Propfind {
     Propname,
     Allprop { include: Option<Include> },
     Prop(Properties),
 }
 
-impl Element for Propfind {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "propfind";
-}
-
 impl TryFrom<&Value> for Propfind {
     type Error = ExtractElementError;
 
@@ -69,74 +64,140 @@ impl From<Propfind> for Value {
 }
 
 /// The `propname` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_propname).
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Propname;
 
-impl Element for Propname {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "propname";
-}
-
-impl TryFrom<&Value> for Propname {
-    type Error = ExtractElementError;
-
-    fn try_from(_: &Value) -> Result<Self, Self::Error> {
-        Ok(Propname)
-    }
-}
-
-impl From<Propname> for Value {
-    fn from(_: Propname) -> Self {
-        Value::Empty
-    }
-}
-
 /// The `allprop` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_allprop).
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Allprop;
 
-impl Element for Allprop {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "allprop";
-}
-
-impl TryFrom<&Value> for Allprop {
-    type Error = ExtractElementError;
-
-    fn try_from(_: &Value) -> Result<Self, Self::Error> {
-        Ok(Allprop)
+/// The `include` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_include).
+///
+/// Contains the names of properties that should be returned in addition to
+/// the ones returned for `allprop`, e.g. live properties that are expensive
+/// to compute.
+///
+/// ```
+/// use webdav_xml::{
+///     elements::{Include, Propfind},
+///     properties::LockDiscovery,
+///     ElementName, FromXml,
+/// };
+///
+/// let xml = r#"
+/// <d:propfind xmlns:d="DAV:" xmlns:ex="http://example.com/ns">
+///   <d:allprop/>
+///   <d:include><d:lockdiscovery/><ex:color/></d:include>
+/// </d:propfind>
+/// "#;
+/// assert_eq!(
+///     Propfind::from_xml(xml)?,
+///     Propfind::Allprop {
+///         include: Some(
+///             Include::new()
+///                 .with_name::<LockDiscovery>()
+///                 .with_by_name(ElementName::new("http://example.com/ns", "color"))
+///         )
+///     }
+/// );
+/// # Ok::<(), webdav_xml::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
+pub struct Include(pub Vec<ElementName<ByteString>>);
+
+impl Include {
+    pub fn new() -> Self {
+        Self::default()
     }
-}
-
-impl From<Allprop> for Value {
-    fn from(_: Allprop) -> Self {
-        Value::Empty
+    pub fn with_name<E>(self) -> Self
+    where
+        E: Element,
+    {
+        self.with_by_name(ElementName::of::<E>())
+    }
+    /// Add a property with a name that is only known at runtime.
+    pub fn with_by_name(mut self, name: ElementName<ByteString>) -> Self {
+        if !self.0.contains(&name) {
+            self.0.push(name);
+        }
+        self
+    }
+    /// List the names of the included properties.
+    pub fn names(&self) -> impl Iterator<Item = &ElementName<ByteString>> {
+        self.0.iter()
+    }
+    /// Check whether a property is included by its namespace and local name.
+    pub fn contains(&self, namespace: Option<&str>, local_name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|name| name.namespace.as_deref() == namespace && name.local_name == local_name)
+    }
+    /// Merge the properties returned for `allprop` with the included ones.
+    ///
+    /// `allprop` contains the properties a server returns for `allprop`,
+    /// while `available` contains all properties of the resource, including
+    /// the ones that are only returned when requested explicitly. Included
+    /// properties that are already part of `allprop` or don't exist in
+    /// `available` are skipped.
+    ///
+    /// ```
+    /// use webdav_xml::{
+    ///     elements::{Include, Properties},
+    ///     properties::{DisplayName, ETag},
+    /// };
+    ///
+    /// let allprop = Properties::new().with(DisplayName("file".into()));
+    /// let available = allprop.clone().with(ETag("\"1\"".into()));
+    ///
+    /// let include = Include::new().with_name::<ETag>();
+    /// assert_eq!(include.merge(allprop.clone(), &available), available);
+    /// ```
+    pub fn merge(&self, mut allprop: Properties, available: &Properties) -> Properties {
+        for name in &self.0 {
+            if allprop
+                .get_by_name(name.namespace.as_deref(), &name.local_name)
+                .is_some()
+            {
+                continue;
+            }
+            if let Some(value) = available.get_by_name(name.namespace.as_deref(), &name.local_name)
+            {
+                allprop.insert_by_name(name.clone(), value.clone());
+            }
+        }
+        allprop
     }
-}
-
-/// The `include` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_include).
-#[derive(Clone, Debug, PartialEq)]
-pub struct Include(Vec<ByteString>);
-
-impl Element for Include {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "include";
 }
 
 impl TryFrom<&Value> for Include {
     type Error = ExtractElementError;
 
-    fn try_from(_: &Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value.content() {
+            Value::Empty => Ok(Self::new()),
+            _ => Ok(value
+                .to_map()?
+                .iter()
+                .fold(Self::new(), |include, (name, _)| {
+                    include.with_by_name(name.clone())
+                })),
+        }
     }
 }
 
 impl From<Include> for Value {
-    fn from(_: Include) -> Self {
-        todo!()
+    fn from(Include(names): Include) -> Self {
+        if names.is_empty() {
+            return Value::Empty;
+        }
+        let mut map = ValueMap::new();
+        for name in names {
+            map.insert_by_name(name, Value::Empty);
+        }
+        Value::Map(map)
     }
 }
 
@@ -207,4 +268,80 @@ mod tests {
 
         assert_eq!(xml.trim(), expected_xml.trim());
     }
+
+    #[test]
+    fn test_allprop_include() -> crate::Result<()> {
+        use crate::{
+            elements::Include,
+            utils::{test_deserialize, test_serialize},
+            ElementName, DAV_NAMESPACE,
+        };
+
+        // http://webdav.org/specs/rfc4918.html#n-example---using-allprop-with-include
+        let xml = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:ex="http://example.com/ns">
+  <d:allprop/>
+  <d:include>
+    <d:supported-live-property-set/>
+    <d:supported-report-set/>
+    <ex:color/>
+  </d:include>
+</d:propfind>
+"#;
+        let propfind = Propfind::Allprop {
+            include: Some(
+                Include::new()
+                    .with_by_name(ElementName::new(
+                        DAV_NAMESPACE,
+                        "supported-live-property-set",
+                    ))
+                    .with_by_name(ElementName::new(DAV_NAMESPACE, "supported-report-set"))
+                    .with_by_name(
+                        ElementName::new("http://example.com/ns", "color").with_prefix("ex"),
+                    ),
+            ),
+        };
+
+        test_deserialize(&propfind, xml)?;
+        test_serialize(xml, propfind)?;
+
+        let xml = r#"<d:propfind xmlns:d="DAV:"><d:allprop/><d:include/></d:propfind>"#;
+        test_deserialize(
+            &Propfind::Allprop {
+                include: Some(Include::new()),
+            },
+            xml,
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_include_merge() {
+        use crate::{elements::Include, properties::DisplayName, ElementName, Value};
+
+        let color = ElementName::new("http://example.com/ns", "color");
+        let allprop = Properties::new().with(DisplayName("file".into()));
+        let available = allprop
+            .clone()
+            .with(ETag("\"1\"".into()))
+            .with_by_name(color.clone(), "red");
+
+        let include = Include::new()
+            .with_name::<DisplayName>()
+            .with_by_name(color.clone())
+            .with_by_name(ElementName::new("http://example.com/ns", "missing"));
+        let merged = include.merge(allprop, &available);
+
+        assert_eq!(
+            merged.names().cloned().collect::<Vec<_>>(),
+            [ElementName::of::<DisplayName>(), color]
+        );
+        assert_eq!(
+            merged.get_by_name(Some("http://example.com/ns"), "color"),
+            Some(&Value::Text("red".into()))
+        );
+        assert!(include.contains(Some("http://example.com/ns"), "missing"));
+    }
 }
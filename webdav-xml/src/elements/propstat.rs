@@ -3,55 +3,18 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::{
-    elements::{Properties, ResponseDescription, Status},
-    value::ValueMap,
-    Element, ExtractElementError, OptionExt, Value, DAV_NAMESPACE, DAV_PREFIX,
+    elements::{DavError, Properties, ResponseDescription, Status},
+    Element, FromValue, IntoValue, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `propstat` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_propstat).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element, FromValue, IntoValue)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Propstat {
     pub prop: Properties,
     pub status: Status,
-    // pub error: Option<Error>,
+    #[xml(optional)]
+    pub error: Option<DavError>,
+    #[xml(optional)]
     pub responsedescription: Option<ResponseDescription>,
 }
-
-impl Element for Propstat {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "propstat";
-}
-
-impl TryFrom<&Value> for Propstat {
-    type Error = ExtractElementError;
-
-    fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        let map = value.to_map()?;
-        Ok(Self {
-            prop: map.get().required::<Properties>()??,
-            status: map.get().required::<Status>()??,
-            responsedescription: map.get().transpose()?,
-        })
-    }
-}
-
-impl From<Propstat> for Value {
-    fn from(
-        Propstat {
-            prop,
-            status,
-            responsedescription,
-        }: Propstat,
-    ) -> Value {
-        let mut map = ValueMap::new();
-
-        map.insert::<Properties>(prop.into());
-        map.insert::<Status>(status.into());
-        if let Some(responsedescription) = responsedescription {
-            map.insert::<ResponseDescription>(responsedescription.into())
-        }
-
-        Value::Map(map)
-    }
-}
@@ -5,37 +5,32 @@
 use nonempty::NonEmpty;
 
 use crate::{
-    elements::{Href, Propstat, ResponseDescription, Status},
+    elements::{DavError, Href, Location, Propstat, ResponseDescription, Status},
     utils::NonEmptyExt,
     value::ValueMap,
     Element, ExtractElementError, OptionExt, Value, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `response` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_response).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub enum Response {
     Propstat {
         href: Href,
         propstat: NonEmpty<Propstat>,
-        // error: Option<Error>,
+        error: Option<DavError>,
         responsedescription: Option<ResponseDescription>,
-        // location: Option<Location>,
+        location: Option<Location>,
     },
     Status {
         href: NonEmpty<Href>,
         status: Status,
-        // error: Option<Error>,
+        error: Option<DavError>,
         responsedescription: Option<ResponseDescription>,
-        // location: Option<Location>,
+        location: Option<Location>,
     },
 }
 
-impl Element for Response {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "response";
-}
-
 impl TryFrom<&Value> for Response {
     type Error = ExtractElementError;
 
@@ -46,12 +41,16 @@ impl TryFrom<&Value> for Response {
             Some(propstat) => Ok(Self::Propstat {
                 href: map.get().required::<Href>()??,
                 propstat,
+                error: map.get().transpose()?,
                 responsedescription: map.get().transpose()?,
+                location: map.get().transpose()?,
             }),
             None => Ok(Self::Status {
                 href: NonEmpty::try_collect(map.iter_all())?.required::<Href>()?,
                 status: map.get().required::<Status>()??,
+                error: map.get().transpose()?,
                 responsedescription: map.get().transpose()?,
+                location: map.get().transpose()?,
             }),
         }
     }
@@ -65,7 +64,9 @@ impl From<Response> for Value {
             Response::Propstat {
                 href,
                 propstat,
+                error,
                 responsedescription,
+                location,
             } => {
                 map.insert::<Href>(href.into());
                 map.insert::<Propstat>(Value::List(Box::new(
@@ -73,14 +74,22 @@ impl From<Response> for Value {
                         "iterator is created from a `NonEmpty` and is guaranteed to be nonempty",
                     ),
                 )));
+                if let Some(error) = error {
+                    map.insert::<DavError>(error.into())
+                }
                 if let Some(responsedescription) = responsedescription {
                     map.insert::<ResponseDescription>(responsedescription.into())
                 }
+                if let Some(location) = location {
+                    map.insert::<Location>(location.into())
+                }
             }
             Response::Status {
                 href,
                 status,
+                error,
                 responsedescription,
+                location,
             } => {
                 map.insert::<Href>(Value::List(Box::new(
                     NonEmpty::collect(href.into_iter().map(Value::from)).expect(
@@ -88,9 +97,15 @@ impl From<Response> for Value {
                     ),
                 )));
                 map.insert::<Status>(status.into());
+                if let Some(error) = error {
+                    map.insert::<DavError>(error.into())
+                }
                 if let Some(responsedescription) = responsedescription {
                     map.insert::<ResponseDescription>(responsedescription.into())
                 }
+                if let Some(location) = location {
+                    map.insert::<Location>(location.into())
+                }
             }
         }
 
@@ -7,15 +7,10 @@ use std::{fmt::Display, str::FromStr};
 use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `status` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_status).
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Status(pub http::StatusCode);
 
-impl Element for Status {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "status";
-}
-
 impl From<http::StatusCode> for Status {
     fn from(code: http::StatusCode) -> Self {
         Self(code)
@@ -62,7 +57,7 @@ pub struct InvalidStatus(String);
 
 impl std::fmt::Display for InvalidStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "invalid status: {}", self.0)
+        write!(f, "invalid status \"{}\"", self.0)
     }
 }
 
@@ -1,42 +1,99 @@
+use std::{
+    ops::Add,
+    time::{Duration, Instant},
+};
+
 use crate::{
     Element, ExtractElementError, ExtractElementErrorKind, Value, DAV_NAMESPACE, DAV_PREFIX,
 };
 
 /// The `timeout` XML element as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_timeout).
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Can be converted from a [`Duration`] and to an absolute expiry time:
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use webdav_xml::elements::Timeout;
+///
+/// let timeout = Timeout::from(Duration::from_secs(600));
+/// assert_eq!(timeout, Timeout::Seconds(600));
+///
+/// let now = Instant::now();
+/// assert_eq!(timeout.expires_at(now), Some(now + Duration::from_secs(600)));
+/// assert_eq!(Timeout::Infinite.expires_at(now), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub enum Timeout {
     Seconds(u32),
     Infinite,
 }
 
-impl Element for Timeout {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "timeout";
+impl Timeout {
+    /// The duration of the timeout, or `None` if it is infinite.
+    pub fn to_duration(self) -> Option<Duration> {
+        match self {
+            Timeout::Seconds(seconds) => Some(Duration::from_secs(seconds.into())),
+            Timeout::Infinite => None,
+        }
+    }
+    /// The time at which a lock with this timeout expires if it was created
+    /// or refreshed at `start`, or `None` if it never expires.
+    ///
+    /// Works with any time type that a [`Duration`] can be added to, e.g.
+    /// [`Instant`], [`SystemTime`](std::time::SystemTime) or
+    /// [`OffsetDateTime`](time::OffsetDateTime).
+    pub fn expires_at<T>(self, start: T) -> Option<T>
+    where
+        T: Add<Duration, Output = T>,
+    {
+        self.to_duration().map(|duration| start + duration)
+    }
+    /// The timeout that remains until `expiry`, rounded up to whole
+    /// seconds.
+    pub fn until(expiry: Instant) -> Self {
+        expiry.saturating_duration_since(Instant::now()).into()
+    }
+}
+
+impl From<Duration> for Timeout {
+    /// Rounds up to whole seconds and saturates at the maximum of
+    /// `2^32 - 1` seconds allowed by RFC 4918.
+    fn from(duration: Duration) -> Self {
+        let seconds = match duration.subsec_nanos() {
+            0 => duration.as_secs(),
+            _ => duration.as_secs().saturating_add(1),
+        };
+        Timeout::Seconds(seconds.try_into().unwrap_or(u32::MAX))
+    }
 }
 
 impl TryFrom<&Value> for Timeout {
     type Error = ExtractElementError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        match value.to_text() {
-            Ok(text) => {
-                if text == "Infinite" {
-                    Ok(Timeout::Infinite)
-                } else if let Some(stripped) = text.strip_prefix("Second-") {
-                    match stripped.parse::<u32>() {
-                        Ok(seconds) => Ok(Timeout::Seconds(seconds)),
-                        Err(_) => Err(ExtractElementError::new(ExtractElementErrorKind::Other(
-                            "Timeout element has invalid Second value".into(),
-                        ))),
-                    }
-                } else {
-                    Err(ExtractElementError::new(ExtractElementErrorKind::Other(
-                        "Timeout element must be 'Infinite' or 'Second-<number>'".into(),
-                    )))
-                }
-            }
-            Err(e) => Err(e),
+        let text = value.to_text()?.trim();
+
+        // RFC 2518 servers may use a different case, and some send values
+        // beyond the maximum of 2^32 - 1 seconds
+        if text.eq_ignore_ascii_case("Infinite") {
+            return Ok(Timeout::Infinite);
+        }
+        match text
+            .get(.."Second-".len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case("Second-"))
+            .map(|prefix| &text[prefix.len()..])
+        {
+            Some(seconds) => match seconds.parse::<u64>() {
+                Ok(seconds) => Ok(Timeout::Seconds(seconds.try_into().unwrap_or(u32::MAX))),
+                Err(_) => Err(ExtractElementError::new(ExtractElementErrorKind::Other(
+                    "Timeout element has invalid Second value".into(),
+                ))),
+            },
+            None => Err(ExtractElementError::new(ExtractElementErrorKind::Other(
+                "Timeout element must be 'Infinite' or 'Second-<number>'".into(),
+            ))),
         }
     }
 }
@@ -109,4 +166,53 @@ mod tests {
 
         assert_eq!(xml.trim(), expected_xml.trim());
     }
+
+    #[test]
+    fn test_deserialize_legacy() {
+        use crate::Value;
+
+        for (text, timeout) in [
+            ("infinite", Timeout::Infinite),
+            (" second-60 ", Timeout::Seconds(60)),
+            ("Second-4294967296", Timeout::Seconds(u32::MAX)),
+        ] {
+            assert_eq!(
+                Timeout::try_from(&Value::Text(text.into())).unwrap(),
+                timeout
+            );
+        }
+        for text in ["Second-", "Second--1", "Seconds-1", "Infinity"] {
+            assert!(Timeout::try_from(&Value::Text(text.into())).is_err());
+        }
+    }
+
+    #[test]
+    fn test_duration() {
+        use std::time::{Duration, Instant};
+
+        assert_eq!(Timeout::from(Duration::ZERO), Timeout::Seconds(0));
+        assert_eq!(
+            Timeout::from(Duration::from_millis(1500)),
+            Timeout::Seconds(2)
+        );
+        assert_eq!(Timeout::from(Duration::MAX), Timeout::Seconds(u32::MAX));
+        assert_eq!(
+            Timeout::Seconds(600).to_duration(),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(Timeout::Infinite.to_duration(), None);
+
+        let start = time::OffsetDateTime::UNIX_EPOCH;
+        assert_eq!(
+            Timeout::Seconds(60).expires_at(start),
+            Some(start + Duration::from_secs(60))
+        );
+
+        let timeout = Timeout::until(Instant::now() + Duration::from_secs(60));
+        assert!(matches!(timeout, Timeout::Seconds(59..=60)));
+        assert_eq!(
+            Timeout::until(Instant::now() - Duration::from_secs(1)),
+            Timeout::Seconds(0)
+        );
+    }
 }
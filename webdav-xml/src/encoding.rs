@@ -0,0 +1,243 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Transcoding of XML documents that aren't encoded as UTF-8.
+//!
+//! With the `encoding` feature enabled, [`FromXml`](crate::FromXml) detects
+//! the encoding of a document from its byte order mark or XML declaration.
+//! Use [`decode`] before reading the document if the `Content-Type` header
+//! specifies a `charset`:
+//!
+//! ```
+//! use webdav_xml::{elements::Properties, encoding, properties::DisplayName, FromXml};
+//!
+//! // `<d:prop xmlns:d="DAV:"><d:displayname>Café</d:displayname></d:prop>`
+//! let body = b"<d:prop xmlns:d=\"DAV:\"><d:displayname>Caf\xe9</d:displayname></d:prop>";
+//!
+//! let xml = encoding::decode(&body[..], Some("ISO-8859-1"))?;
+//! let prop = Properties::from_xml(xml)?;
+//! let displayname = prop.get::<DisplayName>().flatten().transpose()?;
+//! assert_eq!(displayname, Some(DisplayName("Café".into())));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`MultistatusDecoder`](crate::MultistatusDecoder) and
+//! [`MultistatusReader`](crate::MultistatusReader) don't transcode documents
+//! and only accept UTF-8.
+
+use bytes::Bytes;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::{XmlError, XmlErrorKind};
+
+/// Transcode `xml` to UTF-8.
+///
+/// The encoding is detected from
+///
+/// 1. the byte order mark,
+/// 2. the `charset` parameter of the `Content-Type` header, if any,
+/// 3. the first characters of a UTF-16 document without a byte order mark,
+/// 4. the `encoding` of the XML declaration.
+///
+/// Documents without any of these are expected to be UTF-8, which is
+/// returned without copying. If the encoding isn't taken from the XML
+/// declaration or the document is transcoded, the declaration is removed, so
+/// the result isn't decoded again by [`FromXml`](crate::FromXml). Positions
+/// in errors refer to the transcoded document.
+pub fn decode(xml: impl Into<Bytes>, charset: Option<&str>) -> Result<Bytes, XmlError> {
+    let xml = xml.into();
+    let (encoding, bom_len) = match Encoding::for_bom(&xml) {
+        Some(found) => found,
+        None => (detect(&xml, charset)?, 0),
+    };
+
+    if encoding == UTF_8 {
+        let xml = xml.slice(bom_len..);
+        // the declaration might name another encoding
+        return match bom_len > 0 || charset.is_some() {
+            true => Ok(xml.slice(declaration_len(&xml)..)),
+            false => Ok(xml),
+        };
+    }
+
+    let decoded = encoding
+        .decode_without_bom_handling_and_without_replacement(&xml[bom_len..])
+        .ok_or_else(|| XmlError::from(XmlErrorKind::InvalidEncoding(encoding.name())))?;
+    let decoded = decoded.as_bytes();
+    Ok(Bytes::copy_from_slice(&decoded[declaration_len(decoded)..]))
+}
+
+fn detect(xml: &[u8], charset: Option<&str>) -> Result<&'static Encoding, XmlError> {
+    if let Some(charset) = charset {
+        return for_label(charset.as_bytes());
+    }
+    match xml {
+        [b'<', 0, b'?', 0, ..] => return Ok(UTF_16LE),
+        [0, b'<', 0, b'?', ..] => return Ok(UTF_16BE),
+        _ => {}
+    }
+    match declared_encoding(xml) {
+        // the declaration could be read, so the document can't be UTF-16
+        Some(label) => match for_label(label)? {
+            encoding if encoding == UTF_16LE || encoding == UTF_16BE => Ok(UTF_8),
+            encoding => Ok(encoding),
+        },
+        None => Ok(UTF_8),
+    }
+}
+
+fn for_label(label: &[u8]) -> Result<&'static Encoding, XmlError> {
+    Encoding::for_label_no_replacement(label).ok_or_else(|| {
+        XmlErrorKind::UnsupportedEncoding(String::from_utf8_lossy(label).into_owned()).into()
+    })
+}
+
+/// The value of the `encoding` pseudo-attribute of the XML declaration.
+fn declared_encoding(xml: &[u8]) -> Option<&[u8]> {
+    let declaration = xml.strip_prefix(b"<?xml")?;
+    if !declaration.first()?.is_ascii_whitespace() {
+        return None;
+    }
+    let end = declaration.windows(2).position(|w| w == b"?>")?;
+    let declaration = &declaration[..end];
+
+    let start = declaration
+        .windows(b"encoding".len())
+        .position(|w| w == b"encoding")?
+        + b"encoding".len();
+    let rest = declaration[start..].trim_ascii_start().strip_prefix(b"=")?;
+    let rest = rest.trim_ascii_start();
+    let quote = *rest.first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let rest = &rest[1..];
+    rest.iter()
+        .position(|&b| b == quote)
+        .map(|end| &rest[..end])
+}
+
+/// The length of the XML declaration at the start of `xml`, if any.
+fn declaration_len(xml: &[u8]) -> usize {
+    match xml.strip_prefix(b"<?xml") {
+        Some(declaration) if declaration.first().is_some_and(u8::is_ascii_whitespace) => {
+            match declaration.windows(2).position(|w| w == b"?>") {
+                Some(end) => b"<?xml".len() + end + 2,
+                None => 0,
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[track_caller]
+    fn assert_decoded(xml: impl Into<Bytes>, charset: Option<&str>, expected: &str) {
+        assert_eq!(decode(xml, charset).unwrap(), expected.as_bytes());
+    }
+
+    fn utf16(xml: &str, le: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if bom {
+            bytes.extend_from_slice(if le { &[0xFF, 0xFE] } else { &[0xFE, 0xFF] });
+        }
+        for unit in xml.encode_utf16() {
+            bytes.extend_from_slice(&if le {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            });
+        }
+        bytes
+    }
+
+    #[test]
+    fn utf8() {
+        assert_decoded("<a>é</a>", None, "<a>é</a>");
+        assert_decoded(
+            r#"<?xml version="1.0" encoding="UTF-8"?><a>é</a>"#,
+            None,
+            r#"<?xml version="1.0" encoding="UTF-8"?><a>é</a>"#,
+        );
+        assert_decoded(&b"\xef\xbb\xbf<a>\xc3\xa9</a>"[..], None, "<a>é</a>");
+    }
+
+    #[test]
+    fn declaration() {
+        assert_decoded(
+            &b"<?xml version=\"1.0\" encoding='ISO-8859-1' ?>\n<a>\xe9</a>"[..],
+            None,
+            "\n<a>é</a>",
+        );
+        // a declaration of UTF-16 in a document that isn't UTF-16
+        assert_decoded(
+            r#"<?xml version="1.0" encoding="UTF-16"?><a>é</a>"#,
+            None,
+            r#"<?xml version="1.0" encoding="UTF-16"?><a>é</a>"#,
+        );
+    }
+
+    #[test]
+    fn utf16_documents() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-16"?><a>é</a>"#;
+        for (le, bom) in [(true, true), (false, true), (true, false), (false, false)] {
+            assert_decoded(utf16(xml, le, bom), None, "<a>é</a>");
+        }
+
+        // detected when reading XML
+        let value = <crate::Value as crate::FromXml>::from_xml(utf16(xml, false, true)).unwrap();
+        assert_eq!(
+            value.to_map().unwrap().get_by_name(None, "a"),
+            Some(&crate::Value::Text("é".into()))
+        );
+    }
+
+    #[test]
+    fn charset() {
+        // the `charset` of the `Content-Type` header overrides the declaration
+        assert_decoded(
+            &b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><a>\xe9</a>"[..],
+            Some("latin1"),
+            "<a>é</a>",
+        );
+        // but not the byte order mark
+        assert_decoded(
+            &b"\xef\xbb\xbf<a>\xc3\xa9</a>"[..],
+            Some("latin1"),
+            "<a>é</a>",
+        );
+
+        // the declaration doesn't apply when the result is read again
+        let xml = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><a>é</a>";
+        assert_decoded(xml, Some("utf-8"), "<a>é</a>");
+        assert_decoded(format!("\u{feff}{xml}"), None, "<a>é</a>");
+        let value = <crate::Value as crate::FromXml>::from_xml(decode(xml, Some("utf-8")).unwrap())
+            .unwrap();
+        assert_eq!(
+            value.to_map().unwrap().get_by_name(None, "a"),
+            Some(&crate::Value::Text("é".into()))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = decode(r#"<?xml version="1.0" encoding="x-unknown"?><a/>"#, None).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            XmlErrorKind::UnsupportedEncoding(label) if label == "x-unknown"
+        ));
+
+        // an unpaired surrogate
+        let error = decode(&b"<\x00\x00\xd8>\x00"[..], Some("UTF-16LE")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            XmlErrorKind::InvalidEncoding("UTF-16LE")
+        ));
+    }
+}
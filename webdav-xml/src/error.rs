@@ -3,6 +3,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use bytes::Bytes;
+use bytestring::ByteString;
+
+use crate::{ElementName, Limit};
 
 /// Alias for `Result<T, Error>`.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -36,6 +39,23 @@ impl std::fmt::Display for Error {
     }
 }
 
+impl Error {
+    /// The position in the document where the error occurred, if known.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Self::Xml(e) => e.position(),
+            Self::ExtractElement(e) => e.position(),
+        }
+    }
+    /// The path to the element that couldn't be extracted.
+    pub fn path(&self) -> Option<&ElementPath> {
+        match self {
+            Self::Xml(_) => None,
+            Self::ExtractElement(e) => Some(e.path()),
+        }
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -49,32 +69,67 @@ impl std::error::Error for Error {
 #[derive(Debug)]
 pub struct ExtractElementError {
     pub kind: ExtractElementErrorKind,
-    #[cfg(debug_assertions)]
-    location: &'static std::panic::Location<'static>,
+    path: ElementPath,
+    position: Option<Position>,
 }
 
 impl ExtractElementError {
-    #[track_caller]
     pub fn new(kind: ExtractElementErrorKind) -> Self {
         Self {
             kind,
-            #[cfg(debug_assertions)]
-            location: std::panic::Location::caller(),
+            path: ElementPath::default(),
+            position: None,
         }
     }
-    #[track_caller]
     pub fn other(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
         Self::new(ExtractElementErrorKind::Other(e.into()))
     }
+    /// Record that the error occurred within the child element `name`.
+    ///
+    /// This is done by [`ValueMap::get`](crate::ValueMap::get) and
+    /// [`ValueMap::iter_all`](crate::ValueMap::iter_all), so it is only
+    /// needed when extracting child elements manually, e.g. with
+    /// [`ValueMap::get_by_name`](crate::ValueMap::get_by_name).
+    pub fn within(mut self, name: ElementName<ByteString>, index: Option<usize>) -> Self {
+        self.path.0.insert(0, PathSegment { name, index });
+        self
+    }
+    /// The path to the element where the error occurred, relative to the
+    /// element that was extracted.
+    pub fn path(&self) -> &ElementPath {
+        &self.path
+    }
+    /// The position of the element where the error occurred, if it was
+    /// extracted from an XML document.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+    pub(crate) fn path_mut(&mut self) -> &mut ElementPath {
+        &mut self.path
+    }
+    /// Look up the position of the element in the document it was read from.
+    pub(crate) fn locate(mut self, xml: &[u8]) -> Self {
+        if self.position.is_none() && !self.path.is_empty() {
+            self.position = crate::read::locate(xml, &self.path)
+                .map(|offset| Position::from_offset(xml, offset));
+        }
+        self
+    }
+    pub(crate) fn map_position(mut self, f: impl FnOnce(Position) -> Position) -> Self {
+        self.position = self.position.map(f);
+        self
+    }
 }
 
 impl std::fmt::Display for ExtractElementError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.kind)?;
-
-        #[cfg(debug_assertions)]
-        write!(f, " at {}", self.location)?;
-
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        if let Some(position) = self.position {
+            write!(f, " ({position})")?;
+        }
         Ok(())
     }
 }
@@ -97,6 +152,7 @@ pub enum ExtractElementErrorKind {
         got: &'static str,
     },
     MissingElement(&'static str),
+    ExpectedOneOf(&'static [&'static str]),
     ConflictingElements(&'static [&'static str]),
     Other(Box<dyn std::error::Error + Send + Sync>),
 }
@@ -108,6 +164,16 @@ impl std::fmt::Display for ExtractElementErrorKind {
                 write!(f, "expected value of type `{expected}`, got `{got}`")
             }
             Self::MissingElement(name) => write!(f, "missing `{name}` element"),
+            Self::ExpectedOneOf(names) => {
+                f.write_str("expected one of ")?;
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{name}`")?;
+                }
+                Ok(())
+            }
             Self::ConflictingElements(names) => {
                 for (i, name) in names.iter().enumerate() {
                     if i > 0 {
@@ -124,42 +190,200 @@ impl std::fmt::Display for ExtractElementErrorKind {
 
 /// Returned when reading or writing XML failed.
 #[derive(Debug)]
-pub enum XmlError {
-    InvalidNamespace(Bytes),
-    UnexpectedTag,
-    Utf8(std::str::Utf8Error),
-    Xml(quick_xml::Error),
+pub struct XmlError {
+    pub kind: XmlErrorKind,
+    position: Option<Position>,
+}
+
+impl XmlError {
+    pub fn new(kind: XmlErrorKind) -> Self {
+        Self {
+            kind,
+            position: None,
+        }
+    }
+    /// The position in the document where the error occurred, if it was
+    /// raised while reading.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+    /// Set the position if it isn't known yet.
+    pub(crate) fn at(mut self, position: impl FnOnce() -> Position) -> Self {
+        if self.position.is_none() {
+            self.position = Some(position());
+        }
+        self
+    }
+    /// Move a known position, e.g. from a fragment to the whole document.
+    pub(crate) fn map_position(mut self, f: impl FnOnce(Position) -> Position) -> Self {
+        self.position = self.position.map(f);
+        self
+    }
+}
+
+impl From<XmlErrorKind> for XmlError {
+    fn from(kind: XmlErrorKind) -> Self {
+        Self::new(kind)
+    }
 }
 
 impl From<std::str::Utf8Error> for XmlError {
     fn from(e: std::str::Utf8Error) -> Self {
-        Self::Utf8(e)
+        Self::new(XmlErrorKind::Utf8(e))
     }
 }
 
 impl From<quick_xml::Error> for XmlError {
     fn from(e: quick_xml::Error) -> Self {
-        Self::Xml(e)
+        Self::new(XmlErrorKind::Xml(e))
     }
 }
 
 impl std::fmt::Display for XmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(position) = self.position {
+            write!(f, " ({position})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for XmlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            XmlErrorKind::Xml(e) => Some(e),
+            XmlErrorKind::Utf8(e) => Some(e),
+            XmlErrorKind::Read(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+/// The reason why reading or writing XML failed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum XmlErrorKind {
+    InvalidNamespace(Bytes),
+    /// The document exceeds one of the configured
+    /// [`ReadLimits`](crate::ReadLimits).
+    LimitExceeded(Limit),
+    /// A tag that doesn't fit into the structure of the document, e.g. the
+    /// start tag of an element that is never closed.
+    UnexpectedTag(Bytes),
+    /// The encoding of the document isn't supported, e.g. because the
+    /// `encoding` feature is disabled.
+    UnsupportedEncoding(String),
+    /// The document isn't valid in the encoding it declares.
+    InvalidEncoding(&'static str),
+    Utf8(std::str::Utf8Error),
+    Xml(quick_xml::Error),
+    /// The input couldn't be read, e.g. because the connection was closed
+    /// while receiving a body.
+    Read(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl std::fmt::Display for XmlErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::InvalidNamespace(bytes) => write!(f, "invalid namespace declaration: {bytes:?}"),
+            Self::LimitExceeded(limit) => write!(f, "limit exceeded: {limit}"),
             Self::Xml(e) => write!(f, "{e}"),
-            Self::UnexpectedTag => write!(f, "unexpected tag"),
+            Self::UnexpectedTag(name) => {
+                write!(f, "unexpected tag `{}`", String::from_utf8_lossy(name))
+            }
+            Self::UnsupportedEncoding(label) => write!(f, "unsupported encoding `{label}`"),
+            Self::InvalidEncoding(encoding) => write!(f, "invalid {encoding} data"),
             Self::Utf8(e) => write!(f, "{e}"),
+            Self::Read(e) => write!(f, "failed to read the input: {e}"),
         }
     }
 }
 
-impl std::error::Error for XmlError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::Xml(e) => Some(e),
-            Self::Utf8(e) => Some(e),
-            _ => None,
+/// A position in an XML document.
+///
+/// Lines and columns start at 1, columns count characters instead of bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    /// The offset in bytes from the start of the document
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl Position {
+    /// The position of the byte `offset` in `xml`.
+    pub fn from_offset(xml: &[u8], offset: usize) -> Self {
+        Self::default().advance(&xml[..offset.min(xml.len())])
+    }
+    /// The position after `bytes`, which directly follow this position.
+    pub(crate) fn advance(mut self, bytes: &[u8]) -> Self {
+        for &byte in bytes {
+            self.offset += 1;
+            match byte {
+                b'\n' => {
+                    self.line += 1;
+                    self.column = 1;
+                }
+                // UTF-8 continuation bytes don't start a new character
+                0x80..=0xBF => {}
+                _ => self.column += 1,
+            }
         }
+        self
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, col {}", self.line, self.column)
+    }
+}
+
+/// The path to an element in a document, e.g.
+/// `multistatus/response[3]/propstat/status`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElementPath(pub(crate) Vec<PathSegment>);
+
+/// An element in an [`ElementPath`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathSegment {
+    pub name: ElementName<ByteString>,
+    /// The position among the elements with the same name, starting at 1, if
+    /// there is more than one
+    pub index: Option<usize>,
+}
+
+impl ElementPath {
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Display for ElementPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(&segment.name.local_name)?;
+            if let Some(index) = segment.index {
+                write!(f, "[{index}]")?;
+            }
+        }
+        Ok(())
     }
 }
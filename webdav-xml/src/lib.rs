@@ -14,12 +14,55 @@
 //! Instead, this crate uses the [`Element`](crate::Element) trait to define an
 //! element and [`FromXml`](crate::FromXml)/[`IntoXml`](crate::IntoXml) for
 //! (de)serialization.
+//!
+//! With the `derive` feature enabled, the [`Element`](derive@crate::Element),
+//! [`FromValue`](derive@crate::FromValue) and
+//! [`IntoValue`](derive@crate::IntoValue) derive macros can be used to
+//! generate these implementations for elements that consist of other elements:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use webdav_xml::{elements::Href, Element, FromValue, IntoValue};
+//!
+//! #[derive(Element, FromValue, IntoValue)]
+//! #[xml(namespace = "http://example.com/ns", prefix = "ex")]
+//! struct Mirrors {
+//!     #[xml(list)]
+//!     href: Vec<Href>,
+//! }
+//! # }
+//! ```
+//!
+//! With the `serde` feature enabled, simple custom properties can also be
+//! read and written using their `serde` implementations, see the `serde`
+//! module.
+//!
+//! XML is expected to be encoded as UTF-8. With the `encoding` feature
+//! enabled, other encodings like UTF-16 or ISO-8859-1 are transcoded, see the
+//! `encoding` module.
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
+extern crate self as webdav_xml;
+
+mod attributes;
+#[cfg(feature = "async")]
+mod body;
+mod canonical;
 mod element;
 pub mod elements;
+#[cfg(feature = "encoding")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encoding")))]
+pub mod encoding;
 mod error;
+mod multistatus_reader;
+mod multistatus_writer;
 pub mod properties;
 mod read;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
 mod utils;
 mod value;
 mod write;
@@ -29,10 +72,30 @@ use bytes::{BufMut, Bytes};
 #[doc(no_inline)]
 pub use nonempty;
 
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use webdav_xml_derive::{Element, FromValue, IntoValue};
+// the derive macros are always used internally
+#[cfg(not(feature = "derive"))]
+use webdav_xml_derive::{Element, FromValue, IntoValue};
+
+pub use self::{
+    attributes::{Attributes, XML_NAMESPACE, XML_PREFIX},
+    element::{Element, ElementName},
+    error::{
+        ElementPath, Error, ExtractElementError, ExtractElementErrorKind, PathSegment, Position,
+        Result, XmlError, XmlErrorKind,
+    },
+    multistatus_reader::{MultistatusDecoder, MultistatusReader},
+    multistatus_writer::MultistatusWriter,
+    read::{Limit, ReadLimits},
+    value::{Node, Value, ValueMap},
+    write::{Escape, LineEnding, SelfClosing, WriteOptions},
+};
+
+#[cfg(feature = "async")]
 pub use self::{
-    element::Element,
-    error::{Error, ExtractElementError, ExtractElementErrorKind, Result, XmlError},
-    value::{Value, ValueMap},
+    body::FromBody, multistatus_reader::ByteStream, multistatus_writer::MultistatusStream,
 };
 
 /// The default WebDAV namespace
@@ -48,12 +111,15 @@ pub const DAV_PREFIX: &str = "d";
 /// [`TryFrom<&Value>`] should be implemented instead, and you get the `FromXml`
 /// implementation for free.
 pub trait FromXml: Sized {
-    fn from_xml(xml: impl Into<bytes::Bytes>) -> crate::Result<Self>;
+    fn from_xml_with(xml: impl Into<bytes::Bytes>, limits: &ReadLimits) -> crate::Result<Self>;
+    fn from_xml(xml: impl Into<bytes::Bytes>) -> crate::Result<Self> {
+        Self::from_xml_with(xml, &ReadLimits::default())
+    }
 }
 
 impl FromXml for Value {
-    fn from_xml(xml: impl Into<bytes::Bytes>) -> crate::Result<Self> {
-        Ok(crate::read::read_xml(xml)?)
+    fn from_xml_with(xml: impl Into<bytes::Bytes>, limits: &ReadLimits) -> crate::Result<Self> {
+        Ok(crate::read::read_xml_with(xml, limits)?)
     }
 }
 
@@ -61,11 +127,11 @@ impl<E> FromXml for E
 where
     E: Element + for<'v> TryFrom<&'v Value, Error = ExtractElementError>,
 {
-    fn from_xml(xml: impl Into<bytes::Bytes>) -> crate::Result<Self> {
-        Ok(Value::from_xml(xml)?
-            .to_map()?
-            .get::<E>()
-            .required::<E>()??)
+    fn from_xml_with(xml: impl Into<bytes::Bytes>, limits: &ReadLimits) -> crate::Result<Self> {
+        let xml = xml.into();
+        let value = Value::from_xml_with(xml.clone(), limits)?;
+        let element = value.to_map()?.get::<E>().required::<E>()?;
+        Ok(element.map_err(|e| e.locate(&xml))?)
     }
 }
 
@@ -77,34 +143,41 @@ where
 /// [`Into<Value>`] should be implemented instead, and you get the `IntoXml`
 /// implementation for free.
 pub trait IntoXml: Sized {
-    fn write_xml(self, writer: impl std::io::Write) -> crate::Result<()>;
-    fn into_xml(self) -> crate::Result<Bytes> {
+    fn write_xml_with(
+        self,
+        options: &WriteOptions,
+        writer: impl std::io::Write,
+    ) -> crate::Result<()>;
+    fn write_xml(self, writer: impl std::io::Write) -> crate::Result<()> {
+        self.write_xml_with(&WriteOptions::default(), writer)
+    }
+    fn into_xml_with(self, options: &WriteOptions) -> crate::Result<Bytes> {
         let mut xml = bytes::BytesMut::new().writer();
-        self.write_xml(&mut xml)?;
+        self.write_xml_with(options, &mut xml)?;
         Ok(xml.into_inner().freeze())
     }
+    fn into_xml(self) -> crate::Result<Bytes> {
+        self.into_xml_with(&WriteOptions::default())
+    }
 }
 
 impl<T> IntoXml for T
 where
     T: Element + Into<Value>,
 {
-    fn write_xml(self, writer: impl std::io::Write) -> crate::Result<()> {
-        Ok(crate::write::write_xml::<T>(writer, self.into())?)
+    fn write_xml_with(
+        self,
+        options: &WriteOptions,
+        writer: impl std::io::Write,
+    ) -> crate::Result<()> {
+        Ok(crate::write::write_xml::<T>(writer, self.into(), options)?)
     }
 }
 
-/// A type that can't be instantiated.
-///
-/// Prevents not yet implemented types from being instantiated.
-#[derive(Clone, Debug, PartialEq)]
-enum Todo {}
-
 pub(crate) trait OptionExt<T> {
     fn required<E: Element>(self) -> std::result::Result<T, ExtractElementError>;
 }
 impl<T> OptionExt<T> for Option<T> {
-    #[track_caller]
     fn required<E: Element>(self) -> std::result::Result<T, ExtractElementError> {
         match self {
             Some(v) => Ok(v),
@@ -114,3 +187,91 @@ impl<T> OptionExt<T> for Option<T> {
         }
     }
 }
+
+/// Helpers used by the code generated by `webdav-xml-derive`. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    use nonempty::NonEmpty;
+
+    use crate::{
+        element::ElementName, utils::NonEmptyExt, Element, ExtractElementError,
+        ExtractElementErrorKind, OptionExt, Value, ValueMap,
+    };
+
+    pub fn required<'v, E>(map: &'v ValueMap) -> Result<E, ExtractElementError>
+    where
+        E: Element + TryFrom<&'v Value, Error = ExtractElementError>,
+    {
+        map.get().required::<E>()?
+    }
+
+    pub fn optional<'v, E>(map: &'v ValueMap) -> Result<Option<E>, ExtractElementError>
+    where
+        E: Element + TryFrom<&'v Value, Error = ExtractElementError>,
+    {
+        map.get().transpose()
+    }
+
+    pub fn nonempty<'v, E>(map: &'v ValueMap) -> Result<NonEmpty<E>, ExtractElementError>
+    where
+        E: Element + TryFrom<&'v Value, Error = ExtractElementError> + 'v,
+    {
+        NonEmpty::try_collect(map.iter_all())?.required::<E>()
+    }
+
+    pub fn list<'v, E>(map: &'v ValueMap) -> Result<Vec<E>, ExtractElementError>
+    where
+        E: Element + TryFrom<&'v Value, Error = ExtractElementError> + 'v,
+    {
+        map.iter_all().collect()
+    }
+
+    pub fn insert<E: Element + Into<Value>>(map: &mut ValueMap, element: E) {
+        map.insert::<E>(element.into())
+    }
+
+    pub fn insert_all<E: Element + Into<Value>>(
+        map: &mut ValueMap,
+        elements: impl IntoIterator<Item = E>,
+    ) {
+        for element in elements {
+            map.insert::<E>(element.into())
+        }
+    }
+
+    /// Checks for a child element in the namespace of `P`.
+    pub fn contains<P: Element>(map: &ValueMap, local_name: &'static str) -> bool {
+        map.0.contains_key(&ElementName {
+            namespace: Some(P::NAMESPACE),
+            prefix: None,
+            local_name,
+        })
+    }
+
+    /// Inserts an empty child element in the namespace of `P`.
+    pub fn insert_empty<P: Element>(map: &mut ValueMap, local_name: &'static str) {
+        map.insert_by_name(
+            ElementName {
+                namespace: Some(P::NAMESPACE.into()),
+                prefix: Some(P::PREFIX.into()),
+                local_name: local_name.into(),
+            },
+            Value::Empty,
+        )
+    }
+
+    pub fn exactly_one<T>(
+        mut found: Vec<T>,
+        names: &'static [&'static str],
+    ) -> Result<T, ExtractElementError> {
+        match found.len() {
+            0 => Err(ExtractElementError::new(
+                ExtractElementErrorKind::ExpectedOneOf(names),
+            )),
+            1 => Ok(found.remove(0)),
+            _ => Err(ExtractElementError::new(
+                ExtractElementErrorKind::ConflictingElements(names),
+            )),
+        }
+    }
+}
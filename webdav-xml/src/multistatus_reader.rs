@@ -0,0 +1,712 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::ops::Range;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{
+    element::ElementExt,
+    elements::{Multistatus, Response, ResponseDescription},
+    read::{is_utf8_label, read_xml_with},
+    Element, ElementName, Error, ExtractElementError, ExtractElementErrorKind, Limit, Position,
+    ReadLimits, XmlError, XmlErrorKind,
+};
+
+/// The number of bytes that are read at once by [`MultistatusReader`].
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Incrementally decodes the `response` elements of a `multistatus`
+/// document that is received in chunks.
+///
+/// Only the part of the document that belongs to the current `response` is
+/// buffered, so memory use doesn't depend on the number of responses. The
+/// [`ReadLimits`] apply to each `response` separately. Each `response` is
+/// extracted using [`TryFrom<&Value>`](crate::Value) like
+/// [`Multistatus::from_xml`](crate::FromXml::from_xml) does.
+///
+/// This is the building block for [`MultistatusReader`], which should be
+/// preferred for [`Read`](std::io::Read) or `AsyncRead` sources.
+///
+/// The document isn't transcoded, even with the `encoding` feature, so it
+/// must be encoded as UTF-8. An XML declaration with another encoding is
+/// reported as [`XmlErrorKind::UnsupportedEncoding`]. Such documents can be
+/// transcoded with `encoding::decode` and read with
+/// [`Multistatus::from_xml`](crate::FromXml::from_xml) instead.
+///
+/// ```
+/// use webdav_xml::MultistatusDecoder;
+///
+/// let mut decoder = MultistatusDecoder::new();
+/// decoder.feed(br#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/a</d:href>"#);
+/// assert!(decoder.decode(false)?.is_none());
+///
+/// decoder.feed(b"<d:status>HTTP/1.1 200 OK</d:status></d:response></d:multistatus>");
+/// assert!(decoder.decode(false)?.is_some());
+/// assert!(decoder.decode(true)?.is_none());
+/// assert!(decoder.is_done());
+/// # Ok::<(), webdav_xml::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct MultistatusDecoder {
+    buffer: BytesMut,
+    /// The start and end tag of the root element, once the start tag has been
+    /// read
+    root: Option<(Bytes, Bytes)>,
+    responsedescription: Option<ResponseDescription>,
+    limits: ReadLimits,
+    /// The position of the start of `buffer` in the document
+    position: Position,
+    /// The number of `response` elements that have been read
+    responses: usize,
+    /// The offset in `buffer` up to which events have been scanned
+    scanned: usize,
+    /// The nesting depth within the root element at `scanned`
+    depth: usize,
+    /// The offset in `buffer` of the child element that is being scanned
+    start: usize,
+    /// Whether the last call to `decode` needs more input to continue
+    incomplete: bool,
+    done: bool,
+}
+
+enum Next {
+    Root {
+        start_tag: Bytes,
+        end_tag: Bytes,
+        end: usize,
+    },
+    Element(Range<usize>),
+    End(usize),
+    Incomplete,
+}
+
+impl MultistatusDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Use `limits` instead of the default [`ReadLimits`].
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+    /// Append the next chunk of the document.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+        self.incomplete = false;
+    }
+    /// Decode the next `response` element.
+    ///
+    /// Returns `Ok(None)` if more input is needed or the end of the
+    /// `multistatus` element has been reached (see
+    /// [`is_done`](Self::is_done)). `eof` indicates that no more input will
+    /// be fed, so an incomplete document is reported as an error.
+    pub fn decode(&mut self, eof: bool) -> Result<Option<Response>, Error> {
+        loop {
+            if self.done || (self.incomplete && !eof) {
+                return Ok(None);
+            }
+
+            match self.next() {
+                Ok(Next::Root {
+                    start_tag,
+                    end_tag,
+                    end,
+                }) => {
+                    self.consume(end);
+                    self.root = Some((start_tag, end_tag));
+                }
+                Ok(Next::Element(range)) => {
+                    let position = self.position.advance(&self.buffer[..range.start]);
+                    let element = self.consume(range.end).slice(range.start..);
+                    if let Some(response) = self.extract(element, position)? {
+                        return Ok(Some(response));
+                    }
+                }
+                Ok(Next::End(end)) => {
+                    self.consume(end);
+                    self.done = true;
+                }
+                Ok(Next::Incomplete) if eof => {
+                    let position = self.position.advance(&self.buffer);
+                    return Err(XmlError::from(quick_xml::Error::UnexpectedEof(
+                        Multistatus::LOCAL_NAME.to_owned(),
+                    ))
+                    .at(|| position)
+                    .into());
+                }
+                // a single element doesn't fit into the buffer
+                Ok(Next::Incomplete) if self.buffer.len() > self.limits.get_max_size() => {
+                    let limit = Limit::Size(self.limits.get_max_size());
+                    return Err(XmlError::from(XmlErrorKind::LimitExceeded(limit)).into());
+                }
+                Ok(Next::Incomplete) => self.incomplete = true,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    /// Whether the end of the `multistatus` element has been reached.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+    /// The `responsedescription` of the `multistatus` element, once it has
+    /// been read.
+    pub fn responsedescription(&self) -> Option<&ResponseDescription> {
+        self.responsedescription.as_ref()
+    }
+
+    /// Remove the first `len` bytes from the buffer.
+    fn consume(&mut self, len: usize) -> Bytes {
+        self.position = self.position.advance(&self.buffer[..len]);
+        self.scanned -= len;
+        self.start = self.start.saturating_sub(len);
+        self.buffer.split_to(len).freeze()
+    }
+    /// Find the next complete element in the buffer.
+    ///
+    /// Scanning continues after the last complete event of the previous call,
+    /// so each byte is only scanned once.
+    fn next(&mut self) -> Result<Next, XmlError> {
+        use quick_xml::events::Event;
+
+        let offset = self.scanned;
+        let mut reader = quick_xml::Reader::from_reader(&self.buffer[offset..]);
+        // the end tag of the root element doesn't have a matching start tag
+        // in the buffer
+        reader.check_end_names(false);
+
+        loop {
+            let position = offset + reader.buffer_position();
+            let event = match reader.read_event() {
+                Ok(event) => event,
+                // the rest of the event hasn't been fed yet
+                Err(quick_xml::Error::UnexpectedEof(_)) => return Ok(Next::Incomplete),
+                Err(e) => {
+                    return Err(
+                        XmlError::from(e).at(|| self.position.advance(&self.buffer[..position]))
+                    )
+                }
+            };
+            let end = offset + reader.buffer_position();
+            if !matches!(event, Event::Text(_) | Event::Eof) {
+                // an end tag or processing instruction that is cut off at the
+                // end of the buffer is reported as complete
+                if self.buffer[..end].last() != Some(&b'>') {
+                    return Ok(Next::Incomplete);
+                }
+                self.scanned = end;
+            }
+            match event {
+                Event::Start(tag) if self.root.is_none() => {
+                    let mut start_tag = BytesMut::with_capacity(tag.len() + 2);
+                    start_tag.put_u8(b'<');
+                    start_tag.put_slice(&tag);
+                    start_tag.put_u8(b'>');
+
+                    let name = tag.name();
+                    let mut end_tag = BytesMut::with_capacity(name.as_ref().len() + 3);
+                    end_tag.put_slice(b"</");
+                    end_tag.put_slice(name.as_ref());
+                    end_tag.put_u8(b'>');
+
+                    return Ok(Next::Root {
+                        start_tag: start_tag.freeze(),
+                        end_tag: end_tag.freeze(),
+                        end,
+                    });
+                }
+                // `<d:multistatus/>` doesn't contain any responses
+                Event::Empty(_) if self.root.is_none() => return Ok(Next::End(end)),
+                Event::End(tag) if self.root.is_none() => {
+                    return Err(XmlError::from(XmlErrorKind::UnexpectedTag(
+                        Bytes::copy_from_slice(tag.name().as_ref()),
+                    ))
+                    .at(|| self.position.advance(&self.buffer[..position])))
+                }
+                Event::Start(_) => {
+                    if self.depth == 0 {
+                        self.start = position;
+                    }
+                    self.depth += 1;
+                }
+                Event::Empty(_) if self.depth == 0 => return Ok(Next::Element(position..end)),
+                Event::End(_) if self.depth == 0 => return Ok(Next::End(end)),
+                Event::End(_) => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        return Ok(Next::Element(self.start..end));
+                    }
+                }
+                // the document isn't transcoded
+                Event::Decl(decl) => {
+                    let encoding = decl.encoding().transpose().map_err(|e| {
+                        XmlError::from(e).at(|| self.position.advance(&self.buffer[..position]))
+                    })?;
+                    if let Some(encoding) = encoding.filter(|encoding| !is_utf8_label(encoding)) {
+                        return Err(XmlError::from(XmlErrorKind::UnsupportedEncoding(
+                            String::from_utf8_lossy(&encoding).into_owned(),
+                        ))
+                        .at(|| self.position.advance(&self.buffer[..position])));
+                    }
+                }
+                Event::Eof => return Ok(Next::Incomplete),
+                _ => {}
+            }
+        }
+    }
+    /// Parse a child element of the `multistatus` element within the
+    /// namespace context of the root element.
+    ///
+    /// `position` is the position of `element` in the document.
+    fn extract(&mut self, element: Bytes, position: Position) -> Result<Option<Response>, Error> {
+        let (start_tag, end_tag) = self
+            .root
+            .as_ref()
+            .expect("elements are only read after the root element");
+
+        let mut xml = BytesMut::with_capacity(start_tag.len() + element.len() + end_tag.len());
+        xml.put_slice(start_tag);
+        xml.put_slice(&element);
+        xml.put_slice(end_tag);
+
+        let xml = xml.freeze();
+
+        // positions in `xml` are relative to the start tag of the root element
+        let start_len = start_tag.len();
+        let to_document = |p: Position| {
+            let offset = p.offset.saturating_sub(start_len).min(element.len());
+            position.advance(&element[..offset])
+        };
+
+        let value =
+            read_xml_with(xml.clone(), &self.limits).map_err(|e| e.map_position(to_document))?;
+        let map = value
+            .to_map()?
+            .0
+            .get(&Multistatus::element_name::<&str>())
+            .ok_or_else(|| {
+                ExtractElementError::new(ExtractElementErrorKind::MissingElement(
+                    Multistatus::LOCAL_NAME,
+                ))
+            })?
+            .to_map()?;
+
+        let root = ElementName::of::<Multistatus>();
+        let locate = |e: ExtractElementError| {
+            e.within(root.clone(), None)
+                .locate(&xml)
+                .map_position(to_document)
+        };
+
+        if let Some(responsedescription) = map.get::<ResponseDescription>() {
+            self.responsedescription = Some(responsedescription.map_err(locate)?);
+        }
+        let Some(response) = map.get::<Response>() else {
+            return Ok(None);
+        };
+        self.responses += 1;
+        response.map(Some).map_err(|e| {
+            let mut e = locate(e);
+            // the buffer only contains the current `response`
+            if let Some(segment) = e.path_mut().0.get_mut(1) {
+                segment.index = Some(self.responses);
+            }
+            e.into()
+        })
+    }
+}
+
+/// Reads the `response` elements of a `multistatus` document one at a time.
+///
+/// `MultistatusReader` is an [`Iterator`] for [`Read`](std::io::Read)
+/// sources. With the `async` feature, it is a `Stream` for
+/// `tokio::io::AsyncRead` sources, and `from_stream` accepts a `Stream` of
+/// [`Bytes`]. Like [`MultistatusDecoder`], it only reads UTF-8 documents.
+///
+/// ```
+/// use webdav_xml::MultistatusReader;
+///
+/// let xml = br#"<?xml version="1.0" encoding="utf-8"?>
+/// <d:multistatus xmlns:d="DAV:">
+///   <d:response>
+///     <d:href>/a</d:href>
+///     <d:status>HTTP/1.1 200 OK</d:status>
+///   </d:response>
+/// </d:multistatus>"#;
+///
+/// for response in MultistatusReader::new(&xml[..]) {
+///     println!("{:?}", response?);
+/// }
+/// # Ok::<(), webdav_xml::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct MultistatusReader<R> {
+    decoder: MultistatusDecoder,
+    reader: R,
+    eof: bool,
+    failed: bool,
+}
+
+impl<R> MultistatusReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            decoder: MultistatusDecoder::new(),
+            reader,
+            eof: false,
+            failed: false,
+        }
+    }
+    /// Use `limits` instead of the default [`ReadLimits`].
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.decoder = self.decoder.with_limits(limits);
+        self
+    }
+    /// The `responsedescription` of the `multistatus` element, once it has
+    /// been read.
+    pub fn responsedescription(&self) -> Option<&ResponseDescription> {
+        self.decoder.responsedescription()
+    }
+    /// Decode the next response from the buffered input.
+    ///
+    /// Returns `None` if more input is needed.
+    fn decode(&mut self) -> Option<Option<Result<Response, Error>>> {
+        if self.failed || self.decoder.is_done() {
+            return Some(None);
+        }
+        match self.decoder.decode(self.eof) {
+            Ok(Some(response)) => Some(Some(Ok(response))),
+            Ok(None) if self.decoder.is_done() => Some(None),
+            Ok(None) => None,
+            Err(e) => Some(self.fail(e)),
+        }
+    }
+    fn fail(&mut self, e: impl Into<Error>) -> Option<Result<Response, Error>> {
+        self.failed = true;
+        Some(Err(e.into()))
+    }
+    fn feed(&mut self, chunk: &[u8]) {
+        match chunk {
+            [] => self.eof = true,
+            chunk => self.decoder.feed(chunk),
+        }
+    }
+}
+
+impl<R> Iterator for MultistatusReader<R>
+where
+    R: std::io::Read,
+{
+    type Item = Result<Response, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0; CHUNK_SIZE];
+        loop {
+            if let Some(item) = self.decode() {
+                return item;
+            }
+            match self.reader.read(&mut buf) {
+                Ok(n) => self.feed(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return self.fail(XmlError::from(XmlErrorKind::Read(e.into()))),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R> futures_core::Stream for MultistatusReader<R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    type Item = Result<Response, Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::{pin::Pin, task::Poll};
+
+        let this = &mut *self;
+        let mut buf = [0; CHUNK_SIZE];
+        loop {
+            if let Some(item) = this.decode() {
+                return Poll::Ready(item);
+            }
+            let mut read_buf = tokio::io::ReadBuf::new(&mut buf);
+            match Pin::new(&mut this.reader).poll_read(cx, &mut read_buf) {
+                Poll::Ready(Ok(())) => this.feed(read_buf.filled()),
+                Poll::Ready(Err(e)) => {
+                    return Poll::Ready(this.fail(XmlError::from(XmlErrorKind::Read(e.into()))))
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> MultistatusReader<ByteStream<S>> {
+    /// Read the responses from a `Stream` of [`Bytes`], e.g. an HTTP body.
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub fn from_stream(stream: S) -> Self {
+        Self::new(ByteStream(stream))
+    }
+}
+
+/// Adapts a `Stream` of [`Bytes`] for use with [`MultistatusReader`].
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Debug)]
+pub struct ByteStream<S>(pub S);
+
+#[cfg(feature = "async")]
+impl<S, E> futures_core::Stream for MultistatusReader<ByteStream<S>>
+where
+    S: futures_core::Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Item = Result<Response, Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::{pin::Pin, task::Poll};
+
+        let this = &mut *self;
+        loop {
+            if let Some(item) = this.decode() {
+                return Poll::Ready(item);
+            }
+            match Pin::new(&mut this.reader.0).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) if chunk.is_empty() => {}
+                Poll::Ready(Some(Ok(chunk))) => this.feed(&chunk),
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(this.fail(XmlError::from(XmlErrorKind::Read(e.into()))))
+                }
+                Poll::Ready(None) => this.feed(&[]),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        elements::{Href, Multistatus, Response, ResponseDescription},
+        FromXml,
+    };
+
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:multistatus xmlns:D="DAV:" xmlns:R="http://ns.example.com/boxschema/">
+  <!-- a comment -->
+  <D:response>
+    <D:href>http://www.example.com/file</D:href>
+    <D:propstat>
+      <D:prop>
+        <R:bigbox><R:BoxType>Box type A</R:BoxType></R:bigbox>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response xmlns:S="urn:example">
+    <D:href>http://www.example.com/other</D:href>
+    <D:propstat>
+      <D:prop><S:size>1</S:size></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>http://www.example.com/missing</D:href>
+    <D:status>HTTP/1.1 404 Not Found</D:status>
+  </D:response>
+  <D:responsedescription>done</D:responsedescription>
+</D:multistatus>
+"#;
+
+    /// Feeds `xml` in chunks of `size` bytes.
+    fn decode_chunked(xml: &[u8], size: usize) -> Result<Vec<Response>, Error> {
+        let mut decoder = MultistatusDecoder::new();
+        let mut responses = Vec::new();
+        for chunk in xml.chunks(size) {
+            decoder.feed(chunk);
+            while let Some(response) = decoder.decode(false)? {
+                responses.push(response);
+            }
+        }
+        while let Some(response) = decoder.decode(true)? {
+            responses.push(response);
+        }
+        assert!(decoder.is_done());
+        assert_eq!(
+            decoder.responsedescription(),
+            Some(&ResponseDescription("done".into()))
+        );
+        Ok(responses)
+    }
+
+    #[test]
+    fn positions() {
+        let xml = XML.replace("404 Not Found", "abc");
+        let message =
+            r#"invalid status "HTTP/1.1 abc" at multistatus/response[3]/status (line 22, col 5)"#;
+
+        let error = Multistatus::from_xml(xml.clone()).unwrap_err();
+        assert_eq!(error.to_string(), message);
+        for size in [1, 7, 64, xml.len()] {
+            let error = decode_chunked(xml.as_bytes(), size).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+
+        let xml = XML.replace("</D:href>\n    <D:status>", "</D:status>\n    <D:status>");
+        let error = decode_chunked(xml.as_bytes(), 16).unwrap_err();
+        assert_eq!(error.position().map(|p| (p.line, p.column)), Some((21, 43)));
+    }
+
+    #[test]
+    fn chunked() -> eyre::Result<()> {
+        let expected = Multistatus::from_xml(XML)?.response;
+        assert_eq!(expected.len(), 3);
+
+        for size in [1, 7, 64, XML.len()] {
+            assert_eq!(decode_chunked(XML.as_bytes(), size)?, expected);
+        }
+
+        // a large response in small chunks is only scanned once
+        let xml = format!(
+            r#"<D:multistatus xmlns:D="DAV:" xmlns:Z="urn:example"><D:response><D:href>/</D:href><D:propstat><D:prop>{}</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response><D:responsedescription>done</D:responsedescription></D:multistatus>"#,
+            "<Z:x>1</Z:x>".repeat(50_000)
+        );
+        assert_eq!(decode_chunked(xml.as_bytes(), 256)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reader() -> eyre::Result<()> {
+        let responses = MultistatusReader::new(XML.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(responses, Multistatus::from_xml(XML)?.response);
+
+        let empty = r#"<D:multistatus xmlns:D="DAV:"/>"#;
+        assert_eq!(MultistatusReader::new(empty.as_bytes()).count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        // truncated document
+        let mut reader = MultistatusReader::new(&XML.as_bytes()[..XML.len() / 2]);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+
+        // invalid response
+        let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response><D:status>HTTP/1.1 200 OK</D:status></D:response></D:multistatus>"#;
+        let mut reader = MultistatusReader::new(xml.as_bytes());
+        assert!(matches!(reader.next(), Some(Err(Error::ExtractElement(_)))));
+
+        // limits
+        let mut reader =
+            MultistatusReader::new(XML.as_bytes()).with_limits(ReadLimits::new().max_depth(4));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::LimitExceeded(Limit::Depth(4)),
+                ..
+            })))
+        ));
+        let mut reader =
+            MultistatusReader::new(XML.as_bytes()).with_limits(ReadLimits::new().max_size(64));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::LimitExceeded(Limit::Size(64)),
+                ..
+            })))
+        ));
+
+        // other encodings than UTF-8
+        let xml = br#"<?xml version="1.0" encoding="ISO-8859-1"?><D:multistatus xmlns:D="DAV:"/>"#;
+        assert!(matches!(
+            MultistatusReader::new(&xml[..]).next(),
+            Some(Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::UnsupportedEncoding(label),
+                ..
+            }))) if label == "ISO-8859-1"
+        ));
+
+        // syntax errors are reported without waiting for more input
+        let mut decoder = MultistatusDecoder::new().with_limits(ReadLimits::new().max_size(64));
+        decoder.feed(br#"<D:multistatus xmlns:D="DAV:"><D:response><!x>"#);
+        assert!(matches!(
+            decoder.decode(false),
+            Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::Xml(quick_xml::Error::UnexpectedBang(b'x')),
+                ..
+            }))
+        ));
+
+        // wrong root element
+        let xml = r#"<D:prop xmlns:D="DAV:"><D:response><D:href>/</D:href></D:response></D:prop>"#;
+        assert!(MultistatusReader::new(xml.as_bytes())
+            .next()
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn href() -> eyre::Result<()> {
+        let mut reader = MultistatusReader::new(XML.as_bytes());
+        match reader.nth(2).transpose()? {
+            Some(Response::Status { href, .. }) => assert_eq!(
+                href.head,
+                Href(http::Uri::from_static("http://www.example.com/missing"))
+            ),
+            response => panic!("unexpected response: {response:?}"),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn stream() -> eyre::Result<()> {
+        use futures::{stream, TryStreamExt};
+
+        let expected = Multistatus::from_xml(XML)?.response;
+
+        let chunks = XML
+            .as_bytes()
+            .chunks(5)
+            .map(|chunk| Ok::<_, std::io::Error>(Bytes::copy_from_slice(chunk)));
+        let responses: Vec<Response> = futures::executor::block_on(
+            MultistatusReader::from_stream(stream::iter(chunks)).try_collect(),
+        )?;
+        assert_eq!(responses, expected);
+
+        let responses: Vec<Response> =
+            futures::executor::block_on(MultistatusReader::new(XML.as_bytes()).try_collect())?;
+        assert_eq!(responses, expected);
+
+        let chunks = [
+            Ok(Bytes::from_static(&XML.as_bytes()[..10])),
+            Err(std::io::Error::other("connection reset")),
+        ];
+        let result: Result<Vec<Response>, _> = futures::executor::block_on(
+            MultistatusReader::from_stream(stream::iter(chunks)).try_collect(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Xml(XmlError {
+                kind: XmlErrorKind::Read(_),
+                ..
+            }))
+        ));
+
+        Ok(())
+    }
+}
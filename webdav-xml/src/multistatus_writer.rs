@@ -0,0 +1,412 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use bytes::{buf::Writer, Bytes, BytesMut};
+use bytestring::ByteString;
+
+use crate::{
+    element::{ElementExt, ElementName},
+    elements::{Multistatus, Response, ResponseDescription},
+    write::XmlWriter,
+    Element, Value, WriteOptions, XmlError,
+};
+
+/// Incrementally writes a `multistatus` element, one [`Response`] at a time.
+///
+/// Unlike [`IntoXml`](crate::IntoXml), this doesn't require all responses
+/// to be held in memory, which is useful for large `PROPFIND` responses.
+///
+/// The XML declaration and the `multistatus` start tag are written
+/// immediately. Namespaces that are passed to
+/// [`with_namespaces`](Self::with_namespaces) or registered with
+/// [`WriteOptions::prefix`] are declared on the `multistatus` element, other
+/// namespaces are declared on each `response` element that uses them. If all
+/// namespaces are declared up front in the order they first appear, the
+/// output is identical to serializing the equivalent [`Multistatus`] with
+/// [`IntoXml`](crate::IntoXml).
+///
+/// ```
+/// use webdav_xml::{
+///     elements::{Href, Response, Status},
+///     MultistatusWriter,
+/// };
+///
+/// let mut writer = MultistatusWriter::new(Vec::new())?;
+/// writer.write_response(Response::Status {
+///     href: nonempty::nonempty![Href("/file".parse()?)],
+///     status: Status(http::StatusCode::NOT_FOUND),
+///     error: None,
+///     responsedescription: None,
+///     location: None,
+/// })?;
+/// let xml = writer.finish()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// To write to an async sink, create the writer with an in-memory buffer
+/// and forward the chunks returned by [`take`](Self::take) after each
+/// response. With the `async` feature, `into_stream` turns a `Stream` of
+/// responses into a `Stream` of [`Bytes`].
+pub struct MultistatusWriter<W>
+where
+    W: std::io::Write,
+{
+    writer: XmlWriter<W>,
+}
+
+impl<W> MultistatusWriter<W>
+where
+    W: std::io::Write,
+{
+    /// Create a new `MultistatusWriter` and write the start of the document.
+    pub fn new(writer: W) -> Result<Self, XmlError> {
+        Self::with_namespaces(writer, std::iter::empty::<(&'static str, &'static str)>())
+    }
+    /// Create a new `MultistatusWriter` which declares the given
+    /// `(namespace, prefix)` pairs on the `multistatus` element.
+    pub fn with_namespaces<N, P>(
+        writer: W,
+        namespaces: impl IntoIterator<Item = (N, P)>,
+    ) -> Result<Self, XmlError>
+    where
+        N: Into<ByteString>,
+        P: Into<ByteString>,
+    {
+        let options = namespaces
+            .into_iter()
+            .fold(WriteOptions::new(), |options, (namespace, prefix)| {
+                options.prefix(namespace, prefix)
+            });
+        Self::with_options(writer, &options)
+    }
+    /// Create a new `MultistatusWriter` which declares each namespace on the
+    /// element that uses it, preferring the prefixes of the given
+    /// `(namespace, prefix)` pairs.
+    pub fn with_local_namespaces<N, P>(
+        writer: W,
+        namespaces: impl IntoIterator<Item = (N, P)>,
+    ) -> Result<Self, XmlError>
+    where
+        N: Into<ByteString>,
+        P: Into<ByteString>,
+    {
+        let options = namespaces
+            .into_iter()
+            .fold(WriteOptions::new(), |options, (namespace, prefix)| {
+                options.prefix(namespace, prefix)
+            });
+        Self::with_options(writer, &options.local_namespaces(true))
+    }
+    /// Create a new `MultistatusWriter` with the given [`WriteOptions`].
+    ///
+    /// Unless [`local_namespaces`](WriteOptions::local_namespaces) is set,
+    /// the namespaces registered with [`WriteOptions::prefix`] are declared
+    /// on the `multistatus` element.
+    pub fn with_options(writer: W, options: &WriteOptions) -> Result<Self, XmlError> {
+        let mut writer = XmlWriter::new(writer, options);
+        writer.write_decl()?;
+
+        let name = Multistatus::element_name();
+        writer.add_namespace(&name);
+        if !options.is_local_namespaces() {
+            for (namespace, prefix) in options.prefixes() {
+                writer.add_namespace(&ElementName {
+                    namespace: Some(namespace.clone()),
+                    prefix: Some(prefix.clone()),
+                    local_name: ByteString::new(),
+                });
+            }
+        }
+        writer.write_start(&name)?;
+
+        Ok(Self { writer })
+    }
+    /// Write a single `response` element.
+    pub fn write_response(&mut self, response: Response) -> Result<(), XmlError> {
+        self.write_element::<Response>(response.into())
+    }
+    fn write_element<E: Element>(&mut self, value: Value) -> Result<(), XmlError> {
+        let name = E::element_name();
+        let scope = self.writer.scope();
+        if !self.writer.is_local_namespaces() {
+            self.writer.resolve_namespaces(&name, &value);
+        }
+        self.writer.write_element(&name, value)?;
+        self.writer.end_scope(scope);
+        Ok(())
+    }
+    /// Write the end of the document and return the underlying writer.
+    pub fn finish(self) -> Result<W, XmlError> {
+        self.finish_with_description(None)
+    }
+    /// Write an optional `responsedescription` element and the end of the
+    /// document and return the underlying writer.
+    pub fn finish_with_description(
+        mut self,
+        responsedescription: Option<ResponseDescription>,
+    ) -> Result<W, XmlError> {
+        if let Some(responsedescription) = responsedescription {
+            self.write_element::<ResponseDescription>(responsedescription.into())?;
+        }
+        self.writer.write_end(&Multistatus::element_name())?;
+        Ok(self.writer.into_inner())
+    }
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+}
+
+impl MultistatusWriter<Writer<BytesMut>> {
+    /// Take the bytes that have been written since the last call.
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    /// use webdav_xml::MultistatusWriter;
+    ///
+    /// let mut writer = MultistatusWriter::new(BytesMut::new().writer())?;
+    /// assert!(writer.take().starts_with(b"<?xml"));
+    /// assert!(writer.take().is_empty());
+    /// # Ok::<(), webdav_xml::XmlError>(())
+    /// ```
+    pub fn take(&mut self) -> Bytes {
+        self.writer.get_mut().get_mut().split().freeze()
+    }
+}
+
+#[cfg(feature = "async")]
+impl MultistatusWriter<Writer<BytesMut>> {
+    /// Turn a stream of responses into a stream of XML chunks.
+    ///
+    /// Each chunk contains a single response, the last chunk contains the end
+    /// of the document. The start of the document is prepended to the first
+    /// chunk, or yielded on its own if the first response isn't ready yet.
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub fn into_stream<S>(self, responses: S) -> MultistatusStream<S>
+    where
+        S: futures_core::Stream<Item = Response> + Unpin,
+    {
+        MultistatusStream {
+            writer: Some(self),
+            responses,
+        }
+    }
+}
+
+/// A stream of XML chunks, created by [`MultistatusWriter::into_stream`].
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct MultistatusStream<S> {
+    writer: Option<MultistatusWriter<Writer<BytesMut>>>,
+    responses: S,
+}
+
+#[cfg(feature = "async")]
+impl<S> futures_core::Stream for MultistatusStream<S>
+where
+    S: futures_core::Stream<Item = Response> + Unpin,
+{
+    type Item = Result<Bytes, XmlError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::{pin::Pin, task::Poll};
+
+        let this = &mut *self;
+        let Some(writer) = &mut this.writer else {
+            return Poll::Ready(None);
+        };
+
+        match Pin::new(&mut this.responses).poll_next(cx) {
+            Poll::Ready(Some(response)) => match writer.write_response(response) {
+                Ok(()) => Poll::Ready(Some(Ok(writer.take()))),
+                Err(e) => {
+                    this.writer = None;
+                    Poll::Ready(Some(Err(e)))
+                }
+            },
+            Poll::Ready(None) => {
+                let result = this
+                    .writer
+                    .take()
+                    .expect("writer is checked above")
+                    .finish()
+                    .map(|buf| buf.into_inner().freeze());
+                Poll::Ready(Some(result))
+            }
+            // don't hold back the start of the document while waiting for
+            // the first response
+            Poll::Pending => match writer.take() {
+                chunk if chunk.is_empty() => Poll::Pending,
+                chunk => Poll::Ready(Some(Ok(chunk))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use nonempty::nonempty;
+
+    use super::*;
+    use crate::{
+        elements::{Href, Properties, Propstat, Status},
+        properties::DisplayName,
+        IntoXml,
+    };
+
+    #[derive(Clone, Debug, PartialEq, crate::Element, crate::FromValue, crate::IntoValue)]
+    #[xml(namespace = "http://example.com/ns", prefix = "ex")]
+    struct Color;
+
+    fn responses() -> Vec<Response> {
+        vec![
+            Response::Propstat {
+                href: Href(http::Uri::from_static("/a")),
+                propstat: nonempty![Propstat {
+                    prop: Properties::new().with(DisplayName("a".into())).with(Color),
+                    status: Status(http::StatusCode::OK),
+                    error: None,
+                    responsedescription: None,
+                }],
+                error: None,
+                responsedescription: None,
+                location: None,
+            },
+            Response::Status {
+                href: nonempty![Href(http::Uri::from_static("/b"))],
+                status: Status(http::StatusCode::NOT_FOUND),
+                error: None,
+                responsedescription: None,
+                location: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn byte_compatible() -> eyre::Result<()> {
+        let multistatus = Multistatus {
+            response: responses(),
+            responsedescription: Some(ResponseDescription("done".into())),
+        };
+
+        let mut writer =
+            MultistatusWriter::with_namespaces(Vec::new(), [("http://example.com/ns", "ex")])?;
+        for response in responses() {
+            writer.write_response(response)?;
+        }
+        let xml = writer.finish_with_description(Some(ResponseDescription("done".into())))?;
+
+        assert_eq!(
+            std::str::from_utf8(&multistatus.into_xml()?)?,
+            std::str::from_utf8(&xml)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> eyre::Result<()> {
+        let xml = MultistatusWriter::new(Vec::new())?.finish()?;
+        assert_eq!(&Multistatus::default().into_xml()?, &xml);
+        Ok(())
+    }
+
+    #[test]
+    fn local_namespaces() -> eyre::Result<()> {
+        let mut writer = MultistatusWriter::new(BytesMut::new().writer())?;
+        let mut chunks = vec![writer.take()];
+        for response in responses().into_iter().chain(responses()) {
+            writer.write_response(response)?;
+            chunks.push(writer.take());
+        }
+        chunks.push(writer.finish()?.into_inner().freeze());
+
+        // the namespace is declared on every response that uses it
+        assert!(std::str::from_utf8(&chunks[1])?
+            .contains(r#"<d:response xmlns:ex="http://example.com/ns">"#));
+        assert!(std::str::from_utf8(&chunks[3])?
+            .contains(r#"<d:response xmlns:ex="http://example.com/ns">"#));
+
+        let xml = chunks.concat();
+        let multistatus = <Multistatus as crate::FromXml>::from_xml(xml)?;
+        assert_eq!(
+            multistatus.response,
+            responses()
+                .into_iter()
+                .chain(responses())
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn options() -> eyre::Result<()> {
+        let options = WriteOptions::new()
+            .prefix("http://example.com/ns", "E")
+            .local_namespaces(true)
+            .compact();
+        let mut writer = MultistatusWriter::with_options(Vec::new(), &options)?;
+        for response in responses() {
+            writer.write_response(response)?;
+        }
+        let xml = String::from_utf8(writer.finish()?)?;
+
+        assert!(!xml.contains('\n'));
+        assert!(xml.contains(r#"<E:color xmlns:E="http://example.com/ns"/>"#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_local_namespaces() -> eyre::Result<()> {
+        let mut writer =
+            MultistatusWriter::with_local_namespaces(Vec::new(), [("http://example.com/ns", "E")])?;
+        for response in responses() {
+            writer.write_response(response)?;
+        }
+        let xml = String::from_utf8(writer.finish()?)?;
+
+        assert!(xml.contains(r#"<d:multistatus xmlns:d="DAV:">"#));
+        assert!(xml.contains(r#"<E:color xmlns:E="http://example.com/ns"/>"#));
+        assert_eq!(
+            <Multistatus as crate::FromXml>::from_xml(xml)?.response,
+            responses()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn stream() -> eyre::Result<()> {
+        use futures::{stream, StreamExt, TryStreamExt};
+
+        let chunks: Vec<Bytes> = futures::executor::block_on(
+            MultistatusWriter::with_namespaces(
+                BytesMut::new().writer(),
+                [("http://example.com/ns", "ex")],
+            )?
+            .into_stream(stream::iter(responses()))
+            .try_collect(),
+        )?;
+        // one chunk per response and the end
+        assert_eq!(chunks.len(), 3);
+
+        let multistatus = Multistatus {
+            response: responses(),
+            responsedescription: None,
+        };
+        assert_eq!(multistatus.into_xml()?, chunks.concat());
+
+        let empty = MultistatusWriter::new(BytesMut::new().writer())?.into_stream(stream::empty());
+        assert_eq!(futures::executor::block_on(empty.count()), 1);
+
+        Ok(())
+    }
+}
@@ -7,15 +7,10 @@ use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `creationdate` property as defined in [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_creationdate).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct CreationDate(pub OffsetDateTime);
 
-impl Element for CreationDate {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "creationdate";
-}
-
 impl TryFrom<&Value> for CreationDate {
     type Error = ExtractElementError;
 
@@ -8,15 +8,10 @@ use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `displayname` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_displayname).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct DisplayName(pub ByteString);
 
-impl Element for DisplayName {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "displayname";
-}
-
 impl TryFrom<&Value> for DisplayName {
     type Error = ExtractElementError;
 
@@ -8,15 +8,10 @@ use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `getcontentlanguage` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_getcontentlanguage).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX, local_name = "getcontentlanguage")]
 pub struct ContentLanguage(pub ByteString);
 
-impl Element for ContentLanguage {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "getcontentlanguage";
-}
-
 impl TryFrom<&Value> for ContentLanguage {
     type Error = ExtractElementError;
 
@@ -6,15 +6,10 @@ use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `getcontentlength` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_getcontentlength).
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX, local_name = "getcontentlength")]
 pub struct ContentLength(pub u64);
 
-impl Element for ContentLength {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "getcontentlength";
-}
-
 impl TryFrom<&Value> for ContentLength {
     type Error = ExtractElementError;
 
@@ -8,15 +8,10 @@ use crate::{Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX};
 
 /// The `getcontenttype` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_getcontenttype).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX, local_name = "getcontenttype")]
 pub struct ContentType(pub Mime);
 
-impl Element for ContentType {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "getcontenttype";
-}
-
 impl TryFrom<&Value> for ContentType {
     type Error = ExtractElementError;
 
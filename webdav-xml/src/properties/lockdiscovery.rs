@@ -2,31 +2,180 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::{Element, ExtractElementError, Todo, Value, DAV_NAMESPACE, DAV_PREFIX};
+use crate::{
+    elements::{ActiveLock, Href},
+    value::ValueMap,
+    Element, ExtractElementError, Value, DAV_NAMESPACE, DAV_PREFIX,
+};
 
-/// Not yet implemented.
-///
 /// The `lockdiscovery` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_lockdiscovery).
-#[derive(Clone, Debug, PartialEq)]
-pub struct LockDiscovery(Todo);
+///
+/// Lists the active locks on a resource, which may be none.
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
+pub struct LockDiscovery(pub Vec<ActiveLock>);
 
-impl Element for LockDiscovery {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "lockdiscovery";
+impl LockDiscovery {
+    /// Read a `lockdiscovery` property from a server that leaves out the
+    /// `lockroot` of its locks.
+    ///
+    /// See [`ActiveLock::try_from_lenient()`].
+    pub fn try_from_lenient(value: &Value, lock_root: &Href) -> Result<Self, ExtractElementError> {
+        let locks = match value.content() {
+            Value::Empty => None,
+            _ => value
+                .to_map()?
+                .get_by_name(Some(DAV_NAMESPACE), ActiveLock::LOCAL_NAME),
+        };
+        match locks {
+            Some(Value::List(locks)) => locks
+                .iter()
+                .map(|lock| ActiveLock::try_from_lenient(lock, lock_root))
+                .collect::<Result<_, _>>()
+                .map(Self),
+            Some(lock) => Ok(Self(vec![ActiveLock::try_from_lenient(lock, lock_root)?])),
+            None => Ok(Self::default()),
+        }
+    }
 }
 
 impl TryFrom<&Value> for LockDiscovery {
     type Error = ExtractElementError;
 
-    fn try_from(_: &Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value.content() {
+            Value::Empty => Ok(Self::default()),
+            _ => value
+                .to_map()?
+                .iter_all()
+                .collect::<Result<_, _>>()
+                .map(Self),
+        }
     }
 }
 
 impl From<LockDiscovery> for Value {
-    fn from(_: LockDiscovery) -> Value {
-        todo!()
+    fn from(LockDiscovery(locks): LockDiscovery) -> Value {
+        if locks.is_empty() {
+            return Value::Empty;
+        }
+        let mut map = ValueMap::new();
+        for lock in locks {
+            map.insert::<ActiveLock>(lock.into());
+        }
+        Value::Map(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::LockDiscovery;
+    use crate::{
+        elements::{Depth, Href, LockScope, LockType, Timeout},
+        utils::{test_deserialize, test_serialize},
+        FromXml, IntoXml,
+    };
+
+    #[test]
+    fn empty() -> eyre::Result<()> {
+        let xml = r#"<d:lockdiscovery xmlns:d="DAV:"/>"#;
+
+        test_deserialize(&LockDiscovery::default(), xml)?;
+        test_serialize(xml, LockDiscovery::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> eyre::Result<()> {
+        // http://webdav.org/specs/rfc4918.html#n-example---retrieving-discovery
+        let xml = r#"
+<D:lockdiscovery xmlns:D='DAV:'>
+  <D:activelock>
+    <D:locktype><D:write/></D:locktype>
+    <D:lockscope><D:exclusive/></D:lockscope>
+    <D:depth>0</D:depth>
+    <D:owner>Jane Smith</D:owner>
+    <D:timeout>Infinite</D:timeout>
+    <D:locktoken>
+      <D:href
+  >urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76</D:href>
+    </D:locktoken>
+    <D:lockroot>
+      <D:href
+  >http://www.example.com/container/</D:href>
+    </D:lockroot>
+  </D:activelock>
+</D:lockdiscovery>
+        "#;
+
+        let lockdiscovery = LockDiscovery::from_xml(xml)?;
+        let [lock] = &lockdiscovery.0[..] else {
+            panic!("expected exactly one lock, got {lockdiscovery:?}");
+        };
+
+        assert_eq!(lock.lock_type, LockType::Write(Default::default()));
+        assert_eq!(lock.lock_scope, LockScope::Exclusive);
+        assert_eq!(lock.depth, Depth::Zero);
+        assert_eq!(lock.timeout, Some(Timeout::Infinite));
+        assert_eq!(
+            lock.owner
+                .as_ref()
+                .and_then(|owner| owner.text())
+                .map(|text| &**text),
+            Some("Jane Smith")
+        );
+        assert_eq!(
+            lock.lock_token.as_ref().unwrap().href.to_string(),
+            "urn:uuid:f81de2ad-7f3d-a1b2-4f3c-00a0c91a9d76"
+        );
+        assert_eq!(
+            lock.lock_root.href.head,
+            Href(http::Uri::from_static("http://www.example.com/container/"))
+        );
+
+        assert_eq!(
+            LockDiscovery::from_xml(lockdiscovery.clone().into_xml()?)?,
+            lockdiscovery
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn lenient() -> eyre::Result<()> {
+        let xml = r#"
+<d:lockdiscovery xmlns:d="DAV:">
+  <d:activelock>
+    <d:locktype><d:write/></d:locktype>
+    <d:lockscope><d:shared/></d:lockscope>
+    <d:depth>infinity</d:depth>
+  </d:activelock>
+  <d:activelock>
+    <d:locktype><d:write/></d:locktype>
+    <d:lockscope><d:shared/></d:lockscope>
+    <d:depth>infinity</d:depth>
+  </d:activelock>
+</d:lockdiscovery>
+        "#;
+        let value = crate::Value::from_xml(xml)?;
+        let value = value
+            .to_map()?
+            .get_by_name(Some("DAV:"), "lockdiscovery")
+            .unwrap();
+        let lock_root = Href(http::Uri::from_static("/container/"));
+
+        assert!(LockDiscovery::try_from(value).is_err());
+        let lockdiscovery = LockDiscovery::try_from_lenient(value, &lock_root)?;
+        assert_eq!(lockdiscovery.0.len(), 2);
+        assert!(lockdiscovery
+            .0
+            .iter()
+            .all(|lock| lock.lock_root.href.head == lock_root));
+
+        Ok(())
     }
 }
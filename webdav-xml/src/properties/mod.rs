@@ -13,6 +13,7 @@ mod getcontenttype;
 mod getetag;
 mod getlastmodified;
 mod lockdiscovery;
+mod registry;
 mod resourcetype;
 mod supportedlock;
 
@@ -25,6 +26,7 @@ pub use self::{
     getetag::ETag,
     getlastmodified::LastModified,
     lockdiscovery::LockDiscovery,
+    registry::{KnownProperty, PropertyRegistry},
     resourcetype::{Collection, ResourceType},
     supportedlock::SupportedLock,
 };
@@ -0,0 +1,324 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{convert::Infallible, sync::Arc};
+
+use bytestring::ByteString;
+use indexmap::IndexMap;
+
+use crate::{
+    properties::{
+        ContentLanguage, ContentLength, ContentType, CreationDate, DisplayName, ETag, LastModified,
+        LockDiscovery, ResourceType, SupportedLock,
+    },
+    Element, ElementName, ExtractElementError, Value,
+};
+
+type Decoder<C> =
+    Arc<dyn Fn(&Value) -> Result<KnownProperty<C>, ExtractElementError> + Send + Sync>;
+
+/// Maps property names to the types they are extracted as, used by
+/// [`Properties::iter_typed`](crate::elements::Properties::iter_typed).
+///
+/// The [default](PropertyRegistry::dav) registry contains the properties of
+/// RFC 4918. Custom properties are extracted as [`KnownProperty::Custom`],
+/// usually with an enum of all custom properties as `C`:
+///
+/// ```
+/// use webdav_xml::{
+///     elements::Properties,
+///     properties::{DisplayName, KnownProperty, PropertyRegistry},
+///     Element, ExtractElementError, FromXml, Value,
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Favorite(bool);
+///
+/// impl Element for Favorite {
+///     const NAMESPACE: &'static str = "http://owncloud.org/ns";
+///     const PREFIX: &'static str = "oc";
+///     const LOCAL_NAME: &'static str = "favorite";
+/// }
+///
+/// impl TryFrom<&Value> for Favorite {
+///     type Error = ExtractElementError;
+///
+///     fn try_from(value: &Value) -> Result<Self, Self::Error> {
+///         Ok(Self(value.to_text()? == "1"))
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum OwnCloud {
+///     Favorite(Favorite),
+/// }
+///
+/// let registry = PropertyRegistry::dav().with(OwnCloud::Favorite);
+///
+/// let prop = Properties::from_xml(
+///     r#"<d:prop xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+///          <d:displayname>Example</d:displayname>
+///          <oc:favorite>1</oc:favorite>
+///        </d:prop>"#,
+/// )?;
+/// let properties = prop.iter_typed(&registry).collect::<Result<Vec<_>, _>>()?;
+/// assert!(matches!(
+///     &properties[..],
+///     [
+///         KnownProperty::DisplayName(DisplayName(name)),
+///         KnownProperty::Custom(_, OwnCloud::Favorite(Favorite(true))),
+///     ] if name == "Example"
+/// ));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct PropertyRegistry<C = Infallible> {
+    decoders: IndexMap<ElementName<ByteString>, Decoder<C>>,
+}
+
+/// A property that was extracted using a [`PropertyRegistry`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum KnownProperty<C = Infallible> {
+    CreationDate(CreationDate),
+    DisplayName(DisplayName),
+    ContentLanguage(ContentLanguage),
+    ContentLength(ContentLength),
+    ContentType(ContentType),
+    ETag(ETag),
+    LastModified(LastModified),
+    LockDiscovery(LockDiscovery),
+    ResourceType(ResourceType),
+    SupportedLock(SupportedLock),
+    /// A property that was added to the registry with
+    /// [`PropertyRegistry::with`] or [`PropertyRegistry::insert`].
+    Custom(ElementName<ByteString>, C),
+    /// A property without a value, e.g. in the response to a `propname`
+    /// request.
+    Empty(ElementName<ByteString>),
+    /// A property that isn't in the registry.
+    Unknown(ElementName<ByteString>, Value),
+}
+
+impl<C> KnownProperty<C> {
+    /// The name of the property.
+    pub fn name(&self) -> ElementName<ByteString> {
+        match self {
+            Self::CreationDate(_) => ElementName::of::<CreationDate>(),
+            Self::DisplayName(_) => ElementName::of::<DisplayName>(),
+            Self::ContentLanguage(_) => ElementName::of::<ContentLanguage>(),
+            Self::ContentLength(_) => ElementName::of::<ContentLength>(),
+            Self::ContentType(_) => ElementName::of::<ContentType>(),
+            Self::ETag(_) => ElementName::of::<ETag>(),
+            Self::LastModified(_) => ElementName::of::<LastModified>(),
+            Self::LockDiscovery(_) => ElementName::of::<LockDiscovery>(),
+            Self::ResourceType(_) => ElementName::of::<ResourceType>(),
+            Self::SupportedLock(_) => ElementName::of::<SupportedLock>(),
+            Self::Custom(name, _) | Self::Empty(name) | Self::Unknown(name, _) => name.clone(),
+        }
+    }
+}
+
+impl PropertyRegistry {
+    /// Create a registry with the properties of RFC 4918.
+    pub fn new() -> Self {
+        Self::dav()
+    }
+}
+
+impl<C> PropertyRegistry<C> {
+    /// Create a registry without any properties.
+    pub fn empty() -> Self {
+        Self {
+            decoders: IndexMap::new(),
+        }
+    }
+    /// Whether the registry contains a property.
+    pub fn contains(&self, namespace: Option<&str>, local_name: &str) -> bool {
+        self.decoders.contains_key(&ElementName {
+            namespace,
+            prefix: None,
+            local_name,
+        })
+    }
+    /// List the names of the properties in the registry.
+    pub fn names(&self) -> impl Iterator<Item = &ElementName<ByteString>> {
+        self.decoders.keys()
+    }
+    /// Extract the property `name` with the given `value`.
+    pub fn decode(
+        &self,
+        name: &ElementName<ByteString>,
+        value: &Value,
+    ) -> Result<KnownProperty<C>, ExtractElementError> {
+        if value.is_empty() {
+            return Ok(KnownProperty::Empty(name.clone()));
+        }
+        match self.decoders.get(name) {
+            Some(decode) => decode(value).map_err(|e| e.within(name.clone(), None)),
+            None => Ok(KnownProperty::Unknown(name.clone(), value.clone())),
+        }
+    }
+}
+
+impl<C: 'static> PropertyRegistry<C> {
+    /// Create a registry with the properties of RFC 4918.
+    pub fn dav() -> Self {
+        Self::empty()
+            .with_dav(KnownProperty::CreationDate)
+            .with_dav(KnownProperty::DisplayName)
+            .with_dav(KnownProperty::ContentLanguage)
+            .with_dav(KnownProperty::ContentLength)
+            .with_dav(KnownProperty::ContentType)
+            .with_dav(KnownProperty::ETag)
+            .with_dav(KnownProperty::LastModified)
+            .with_dav(KnownProperty::LockDiscovery)
+            .with_dav(KnownProperty::ResourceType)
+            .with_dav(KnownProperty::SupportedLock)
+    }
+    fn with_dav<P>(mut self, variant: fn(P) -> KnownProperty<C>) -> Self
+    where
+        P: Element + for<'v> TryFrom<&'v Value, Error = ExtractElementError> + 'static,
+    {
+        self.insert_by_name(ElementName::of::<P>(), move |value| {
+            P::try_from(value).map(variant)
+        });
+        self
+    }
+    /// Add the property `P`, which is extracted as
+    /// [`KnownProperty::Custom`] with the result of `f`.
+    pub fn with<P>(mut self, f: impl Fn(P) -> C + Send + Sync + 'static) -> Self
+    where
+        P: Element + for<'v> TryFrom<&'v Value, Error = ExtractElementError> + 'static,
+    {
+        self.insert(f);
+        self
+    }
+    /// Add the property `P`, which is extracted as
+    /// [`KnownProperty::Custom`] with the result of `f`.
+    pub fn insert<P>(&mut self, f: impl Fn(P) -> C + Send + Sync + 'static)
+    where
+        P: Element + for<'v> TryFrom<&'v Value, Error = ExtractElementError> + 'static,
+    {
+        self.insert_by_name(ElementName::of::<P>(), move |value| {
+            P::try_from(value).map(|p| KnownProperty::Custom(ElementName::of::<P>(), f(p)))
+        });
+    }
+    /// Add a property with a name that is only known at runtime.
+    ///
+    /// An existing property with the same name is replaced.
+    pub fn insert_by_name(
+        &mut self,
+        name: ElementName<ByteString>,
+        decode: impl Fn(&Value) -> Result<KnownProperty<C>, ExtractElementError> + Send + Sync + 'static,
+    ) {
+        self.decoders.insert(name, Arc::new(decode));
+    }
+}
+
+impl<C: 'static> Default for PropertyRegistry<C> {
+    fn default() -> Self {
+        Self::dav()
+    }
+}
+
+impl<C> Clone for PropertyRegistry<C> {
+    fn clone(&self) -> Self {
+        Self {
+            decoders: self.decoders.clone(),
+        }
+    }
+}
+
+impl<C> std::fmt::Debug for PropertyRegistry<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.decoders.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{elements::Properties, FromXml};
+
+    #[derive(Debug, PartialEq, Element)]
+    #[xml(namespace = "urn:example", prefix = "ex", local_name = "rating")]
+    struct Rating(u8);
+
+    impl TryFrom<&Value> for Rating {
+        type Error = ExtractElementError;
+
+        fn try_from(value: &Value) -> Result<Self, Self::Error> {
+            value
+                .to_text()?
+                .parse()
+                .map(Self)
+                .map_err(ExtractElementError::other)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Example {
+        Rating(Rating),
+    }
+
+    const XML: &str = r#"
+    <d:prop xmlns:d="DAV:" xmlns:ex="urn:example">
+      <d:getcontentlength>42</d:getcontentlength>
+      <d:resourcetype><d:collection/></d:resourcetype>
+      <d:getetag/>
+      <ex:rating>5</ex:rating>
+      <ex:color>red</ex:color>
+    </d:prop>
+    "#;
+
+    #[test]
+    fn iter_typed() -> eyre::Result<()> {
+        let prop = Properties::from_xml(XML)?;
+
+        let registry = PropertyRegistry::new();
+        let properties = prop.iter_typed(&registry).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            properties,
+            [
+                KnownProperty::ContentLength(ContentLength(42)),
+                KnownProperty::ResourceType(ResourceType::collection()),
+                KnownProperty::Empty(ElementName::of::<ETag>()),
+                KnownProperty::Unknown(ElementName::new("urn:example", "rating"), "5".into()),
+                KnownProperty::Unknown(ElementName::new("urn:example", "color"), "red".into()),
+            ]
+        );
+
+        let registry = PropertyRegistry::dav().with(Example::Rating);
+        let properties = prop.iter_typed(&registry).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            properties[3],
+            KnownProperty::Custom(ElementName::of::<Rating>(), Example::Rating(Rating(5)))
+        );
+        assert_eq!(
+            properties[3].name(),
+            ElementName::new("urn:example", "rating")
+        );
+        assert_eq!(properties[0].name(), ElementName::of::<ContentLength>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> eyre::Result<()> {
+        let prop = Properties::from_xml(XML.replace(">5<", ">many<"))?;
+        let registry = PropertyRegistry::empty().with(Example::Rating);
+        assert!(registry.contains(Some("urn:example"), "rating"));
+        assert!(!registry.contains(Some("DAV:"), "getetag"));
+
+        let error = prop
+            .iter_typed(&registry)
+            .find_map(Result::err)
+            .expect("the rating is invalid");
+        assert_eq!(error.path().to_string(), "rating");
+
+        Ok(())
+    }
+}
@@ -11,15 +11,10 @@ use crate::{
 
 /// The `resourcetype` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_resourcetype).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct ResourceType(ValueMap);
 
-impl Element for ResourceType {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "resourcetype";
-}
-
 impl ResourceType {
     pub fn empty() -> Self {
         Self(Default::default())
@@ -60,14 +55,10 @@ impl From<ResourceType> for Value {
 
 /// The `collection` XML element as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#ELEMENT_collection).
+#[derive(Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
 pub struct Collection;
 
-impl Element for Collection {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "collection";
-}
-
 #[cfg(test)]
 mod tests {
     use crate::utils::{test_deserialize, test_serialize};
@@ -2,31 +2,97 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::{Element, ExtractElementError, Todo, Value, DAV_NAMESPACE, DAV_PREFIX};
+use crate::{
+    elements::LockEntry, value::ValueMap, Element, ExtractElementError, Value, DAV_NAMESPACE,
+    DAV_PREFIX,
+};
 
-/// Not yet implemented.
-///
 /// The `supportedlock` property as defined in
 /// [RFC 4918](http://webdav.org/specs/rfc4918.html#PROPERTY_supportedlock).
-#[derive(Clone, Debug, PartialEq)]
-pub struct SupportedLock(Todo);
-
-impl Element for SupportedLock {
-    const NAMESPACE: &'static str = DAV_NAMESPACE;
-    const PREFIX: &'static str = DAV_PREFIX;
-    const LOCAL_NAME: &'static str = "supportedlock";
-}
+///
+/// Lists the lock capabilities of a resource, which may be none.
+#[derive(Clone, Debug, Default, PartialEq, Element)]
+#[xml(namespace = DAV_NAMESPACE, prefix = DAV_PREFIX)]
+pub struct SupportedLock(pub Vec<LockEntry>);
 
 impl TryFrom<&Value> for SupportedLock {
     type Error = ExtractElementError;
 
-    fn try_from(_: &Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value.content() {
+            Value::Empty => Ok(Self::default()),
+            _ => value
+                .to_map()?
+                .iter_all()
+                .collect::<Result<_, _>>()
+                .map(Self),
+        }
     }
 }
 
 impl From<SupportedLock> for Value {
-    fn from(_: SupportedLock) -> Value {
-        todo!()
+    fn from(SupportedLock(entries): SupportedLock) -> Value {
+        if entries.is_empty() {
+            return Value::Empty;
+        }
+        let mut map = ValueMap::new();
+        for entry in entries {
+            map.insert::<LockEntry>(entry.into());
+        }
+        Value::Map(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SupportedLock;
+    use crate::{
+        elements::{LockEntry, LockScope, LockType},
+        utils::{test_deserialize, test_serialize},
+    };
+
+    #[test]
+    fn example() -> eyre::Result<()> {
+        // http://webdav.org/specs/rfc4918.html#n-example---retrieving-the-supportedlock-property
+        let xml = r#"
+<d:supportedlock xmlns:d="DAV:">
+  <d:lockentry>
+    <d:lockscope>
+      <d:exclusive/>
+    </d:lockscope>
+    <d:locktype>
+      <d:write/>
+    </d:locktype>
+  </d:lockentry>
+  <d:lockentry>
+    <d:lockscope>
+      <d:shared/>
+    </d:lockscope>
+    <d:locktype>
+      <d:write/>
+    </d:locktype>
+  </d:lockentry>
+</d:supportedlock>
+        "#;
+        let supportedlock = SupportedLock(vec![
+            LockEntry {
+                lock_scope: LockScope::Exclusive,
+                lock_type: LockType::Write(Default::default()),
+            },
+            LockEntry {
+                lock_scope: LockScope::Shared,
+                lock_type: LockType::Write(Default::default()),
+            },
+        ]);
+
+        test_deserialize(&supportedlock, xml)?;
+        test_serialize(xml, supportedlock)?;
+
+        test_deserialize(
+            &SupportedLock::default(),
+            r#"<d:supportedlock xmlns:d="DAV:"/>"#,
+        )?;
+
+        Ok(())
     }
 }
@@ -2,28 +2,406 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashSet};
 
 use bytestring::ByteString;
 
-use crate::{element::ElementName, utils::BytesExt, value::ValueMap, Value, XmlError};
+use crate::{
+    element::ElementName,
+    utils::BytesExt,
+    value::{Node, ValueMap},
+    Attributes, ElementPath, Position, Value, XmlError, XmlErrorKind,
+};
 
-pub(crate) fn read_xml(xml: impl Into<bytes::Bytes>) -> Result<Value, XmlError> {
+pub(crate) fn read_xml_with(
+    xml: impl Into<bytes::Bytes>,
+    limits: &ReadLimits,
+) -> Result<Value, XmlError> {
     let xml = xml.into();
-    let mut reader = XmlReader::new(&xml);
-    reader.read_into_value(&xml)
+    if xml.len() > limits.max_size {
+        return Err(limit_exceeded(Limit::Size(limits.max_size)));
+    }
+    #[cfg(feature = "encoding")]
+    let xml = crate::encoding::decode(xml, None)?;
+    #[cfg(not(feature = "encoding"))]
+    check_encoding(&xml)?;
+    let mut reader = XmlReader::new(&xml, limits);
+    reader
+        .read_into_value(&xml)
+        .map_err(|e| e.at(|| Position::from_offset(&xml, reader.position)))
+}
+
+/// Reject documents that aren't encoded as UTF-8 according to their byte
+/// order mark or XML declaration, which can only be read with the `encoding`
+/// feature.
+#[cfg(not(feature = "encoding"))]
+fn check_encoding(xml: &[u8]) -> Result<(), XmlError> {
+    use quick_xml::events::Event;
+
+    let unsupported = |label: &str| Err(XmlErrorKind::UnsupportedEncoding(label.to_owned()).into());
+    match xml {
+        [0xFF, 0xFE, ..] | [b'<', 0, b'?', 0, ..] => return unsupported("UTF-16LE"),
+        [0xFE, 0xFF, ..] | [0, b'<', 0, b'?', ..] => return unsupported("UTF-16BE"),
+        _ => {}
+    }
+    match quick_xml::Reader::from_reader(xml).read_event() {
+        Ok(Event::Decl(decl)) => match decl.encoding().and_then(Result::ok) {
+            Some(label) if !is_utf8_label(&label) => unsupported(&String::from_utf8_lossy(&label)),
+            _ => Ok(()),
+        },
+        // other errors are reported while reading the document
+        _ => Ok(()),
+    }
+}
+
+/// Whether `label` is the `encoding` of an XML declaration for UTF-8.
+pub(crate) fn is_utf8_label(label: &[u8]) -> bool {
+    label.eq_ignore_ascii_case(b"utf-8") || label.eq_ignore_ascii_case(b"utf8")
+}
+
+fn limit_exceeded(limit: Limit) -> XmlError {
+    XmlErrorKind::LimitExceeded(limit).into()
+}
+
+/// Finds the offset of the start tag of the element at `path`.
+///
+/// This reads the document again, so it should only be used to report
+/// errors.
+pub(crate) fn locate(xml: &[u8], path: &ElementPath) -> Option<usize> {
+    use quick_xml::{events::Event, name::ResolveResult};
+
+    let mut reader = quick_xml::NsReader::from_reader(xml);
+    let mut segments = path.segments().iter();
+    let mut segment = segments.next()?;
+    // the number of siblings with the name of `segment`
+    let mut count = 0;
+    // the depth relative to the last element of `path` that was found
+    let mut depth = 0usize;
+
+    loop {
+        let position = reader.buffer_position();
+        let (resolve_result, event) = reader.read_resolved_event().ok()?;
+        let (tag, is_start) = match event {
+            Event::Start(tag) => (tag, true),
+            Event::Empty(tag) => (tag, false),
+            Event::End(_) if depth == 0 => return None,
+            Event::End(_) => {
+                depth -= 1;
+                continue;
+            }
+            Event::Eof => return None,
+            _ => continue,
+        };
+
+        let matches = depth == 0
+            && match resolve_result {
+                ResolveResult::Bound(ns) => {
+                    segment.name.namespace.as_deref().map(str::as_bytes) == Some(ns.as_ref())
+                        && segment.name.local_name.as_bytes() == tag.local_name().as_ref()
+                }
+                ResolveResult::Unbound | ResolveResult::Unknown(_) => {
+                    segment.name.namespace.is_none()
+                        && segment.name.local_name.as_bytes() == tag.name().as_ref()
+                }
+            };
+        if matches {
+            count += 1;
+            if count == segment.index.unwrap_or(1) {
+                match segments.next() {
+                    None => return Some(position),
+                    Some(next) if is_start => {
+                        segment = next;
+                        count = 0;
+                        continue;
+                    }
+                    Some(_) => return None,
+                }
+            }
+        }
+        if is_start {
+            depth += 1;
+        }
+    }
+}
+
+/// Limits for reading XML, used by
+/// [`FromXml::from_xml_with`](crate::FromXml::from_xml_with).
+///
+/// The defaults protect servers against hostile request bodies, e.g. deeply
+/// nested elements that would overflow the stack, while being far above what
+/// legitimate WebDAV documents need. If a limit is exceeded, reading fails with
+/// [`XmlErrorKind::LimitExceeded`].
+///
+/// ```
+/// use webdav_xml::{elements::Propfind, Error, FromXml, Limit, ReadLimits, XmlError, XmlErrorKind};
+///
+/// let limits = ReadLimits::new().max_depth(2);
+/// let xml = r#"<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+/// assert!(matches!(
+///     Propfind::from_xml_with(xml, &limits),
+///     Err(Error::Xml(XmlError {
+///         kind: XmlErrorKind::LimitExceeded(Limit::Depth(2)),
+///         ..
+///     }))
+/// ));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReadLimits {
+    max_size: usize,
+    max_depth: usize,
+    max_elements: usize,
+    max_text: usize,
+    max_attributes: usize,
+    max_namespaces: usize,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            max_size: 16 * 1024 * 1024,
+            max_depth: 128,
+            max_elements: 100_000,
+            max_text: 1024 * 1024,
+            max_attributes: 64,
+            max_namespaces: 64,
+        }
+    }
 }
-struct XmlReader<'x> {
+
+impl ReadLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Don't limit the input at all. Only use this for trusted input, as deeply
+    /// nested elements can overflow the stack.
+    pub fn unlimited() -> Self {
+        Self {
+            max_size: usize::MAX,
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+            max_text: usize::MAX,
+            max_attributes: usize::MAX,
+            max_namespaces: usize::MAX,
+        }
+    }
+    /// The maximum size of the document in bytes. Defaults to 16 MiB.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+    /// The maximum nesting depth of elements, where the root element has a
+    /// depth of 1. Defaults to 128.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+    /// The maximum number of elements in the document. Defaults to 100 000.
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = max_elements;
+        self
+    }
+    /// The maximum size of the text content of an element in bytes, after
+    /// unescaping. Defaults to 1 MiB.
+    pub fn max_text(mut self, max_text: usize) -> Self {
+        self.max_text = max_text;
+        self
+    }
+    /// The maximum number of attributes of an element, not counting namespace
+    /// declarations. Defaults to 64.
+    pub fn max_attributes(mut self, max_attributes: usize) -> Self {
+        self.max_attributes = max_attributes;
+        self
+    }
+    /// The maximum number of namespace declarations on an element. Defaults
+    /// to 64.
+    pub fn max_namespaces(mut self, max_namespaces: usize) -> Self {
+        self.max_namespaces = max_namespaces;
+        self
+    }
+    pub fn get_max_size(&self) -> usize {
+        self.max_size
+    }
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+    pub fn get_max_elements(&self) -> usize {
+        self.max_elements
+    }
+    pub fn get_max_text(&self) -> usize {
+        self.max_text
+    }
+    pub fn get_max_attributes(&self) -> usize {
+        self.max_attributes
+    }
+    pub fn get_max_namespaces(&self) -> usize {
+        self.max_namespaces
+    }
+}
+
+/// The limit of [`ReadLimits`] that was exceeded, with its configured value.
+///
+/// Servers should respond with `413 Payload Too Large` for
+/// [`Size`](Self::Size) and `400 Bad Request` otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Size(usize),
+    Depth(usize),
+    Elements(usize),
+    Text(usize),
+    Attributes(usize),
+    Namespaces(usize),
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Size(max) => write!(f, "the document is larger than {max} bytes"),
+            Self::Depth(max) => write!(f, "elements are nested deeper than {max} levels"),
+            Self::Elements(max) => write!(f, "the document contains more than {max} elements"),
+            Self::Text(max) => write!(f, "an element contains more than {max} bytes of text"),
+            Self::Attributes(max) => write!(f, "an element has more than {max} attributes"),
+            Self::Namespaces(max) => {
+                write!(f, "an element declares more than {max} namespaces")
+            }
+        }
+    }
+}
+
+/// Collects the children of an element in document order and decides how
+/// they are represented as a [`Value`].
+#[derive(Default)]
+struct Children {
+    nodes: Vec<Node>,
+    /// Adjacent text (e.g. text followed by CDATA) that is merged into a
+    /// single text node by [`flush_text`](Self::flush_text)
+    text: Vec<ByteString>,
+    text_len: usize,
+    has_text: bool,
+    has_elements: bool,
+}
+
+impl Children {
+    /// Adds a text node, merging it with a directly preceding one (e.g. text
+    /// followed by CDATA).
+    ///
+    /// Returns the length of the resulting text node.
+    fn push_text(&mut self, text: ByteString) -> usize {
+        if text.is_empty() {
+            return self.text_len;
+        }
+        if !text.chars().all(char::is_whitespace) {
+            self.has_text = true;
+        }
+        self.text_len += text.len();
+        self.text.push(text);
+        self.text_len
+    }
+    fn flush_text(&mut self) {
+        let text = match self.text.len() {
+            0 => return,
+            1 => self.text.remove(0),
+            _ => {
+                let mut merged = String::with_capacity(self.text_len);
+                for text in self.text.drain(..) {
+                    merged.push_str(&text);
+                }
+                merged.into()
+            }
+        };
+        self.text_len = 0;
+        self.nodes.push(Node::Text(text));
+    }
+    fn push_element(&mut self, name: ElementName<ByteString>, value: Value) {
+        self.flush_text();
+        self.has_elements = true;
+        self.nodes.push(Node::Element(name, value));
+    }
+    fn into_value(mut self) -> Value {
+        self.flush_text();
+        // `<a></a>` is equivalent to `<a/>`
+        if self.nodes.is_empty() {
+            return Value::Empty;
+        }
+        match (self.has_text, self.has_elements) {
+            // whitespace between elements is only significant if there is
+            // other text
+            (true, true) => Value::Mixed(self.nodes),
+            // adjacent text nodes are merged, so there is exactly one
+            (true, false) => match self.nodes.into_iter().next() {
+                Some(Node::Text(text)) => Value::Text(text),
+                _ => Value::Empty,
+            },
+            // only whitespace, which is kept to write it back unchanged
+            (false, false) => Value::Mixed(self.nodes),
+            // a map would reorder the elements
+            (false, true) if self.is_interleaved() => Value::Mixed(self.nodes),
+            (false, true) => {
+                let mut map = ValueMap::new();
+                for node in self.nodes {
+                    if let Node::Element(name, value) = node {
+                        map.insert_by_name(name, value);
+                    }
+                }
+                Value::Map(map)
+            }
+        }
+    }
+    /// Whether an element follows an element with another name after an
+    /// element with the same name, e.g. `<a/><b/><a/>`.
+    fn is_interleaved(&self) -> bool {
+        let mut seen = HashSet::new();
+        let mut previous = None;
+        for node in &self.nodes {
+            if let Node::Element(name, _) = node {
+                if previous != Some(name) && !seen.insert(name) {
+                    return true;
+                }
+                previous = Some(name);
+            }
+        }
+        false
+    }
+}
+
+struct XmlReader<'x, 'l> {
     reader: quick_xml::NsReader<&'x [u8]>,
     last: Option<quick_xml::events::Event<'x>>,
+    limits: &'l ReadLimits,
+    /// The offset of the last event that was read
+    position: usize,
+    /// The nesting depth of the element that is currently read
+    depth: usize,
+    /// The number of elements that have been read
+    elements: usize,
 }
 
-impl<'x> XmlReader<'x> {
-    fn new(xml: &'x [u8]) -> Self {
+impl<'x, 'l> XmlReader<'x, 'l> {
+    fn new(xml: &'x [u8], limits: &'l ReadLimits) -> Self {
         Self {
             reader: quick_xml::NsReader::from_reader(xml),
             last: None,
+            limits,
+            position: 0,
+            depth: 0,
+            elements: 0,
+        }
+    }
+    /// Counts an element at `depth` and checks the limits.
+    fn check_element(&mut self, depth: usize) -> Result<(), XmlError> {
+        self.elements += 1;
+        if depth > self.limits.max_depth {
+            return Err(limit_exceeded(Limit::Depth(self.limits.max_depth)));
         }
+        if self.elements > self.limits.max_elements {
+            return Err(limit_exceeded(Limit::Elements(self.limits.max_elements)));
+        }
+        Ok(())
+    }
+    fn check_text(&self, len: usize) -> Result<(), XmlError> {
+        if len > self.limits.max_text {
+            return Err(limit_exceeded(Limit::Text(self.limits.max_text)));
+        }
+        Ok(())
     }
     fn last(&self) -> Option<&quick_xml::events::Event<'x>> {
         self.last.as_ref()
@@ -32,8 +410,9 @@ impl<'x> XmlReader<'x> {
         &mut self,
     ) -> quick_xml::Result<(
         quick_xml::name::ResolveResult<'_>,
-        quick_xml::events::Event<'_>,
+        quick_xml::events::Event<'x>,
     )> {
+        self.position = self.reader.buffer_position();
         let (resolve_result, event) = self.reader.read_resolved_event()?;
         self.last = Some(event.clone());
         Ok((resolve_result, event))
@@ -53,7 +432,10 @@ impl<'x> XmlReader<'x> {
             match resolve_result {
                 ResolveResult::Bound(ns) => {
                     if ns.as_ref().is_empty() {
-                        return Err(XmlError::InvalidNamespace(xml.maybe_slice_ref(ns.as_ref())));
+                        return Err(XmlErrorKind::InvalidNamespace(
+                            xml.maybe_slice_ref(ns.as_ref()),
+                        )
+                        .into());
                     }
 
                     Ok(ElementName {
@@ -70,53 +452,117 @@ impl<'x> XmlReader<'x> {
             }
         }
 
-        let mut map = ValueMap::new();
+        let mut children = Children::default();
 
         loop {
             let (resolve_result, event) = self.read_resolved_event()?;
-            // dbg!(&event);
             match event {
-                Event::Text(text)
-                    if std::str::from_utf8(&text)
-                        .is_ok_and(|s| s.chars().all(char::is_whitespace)) =>
-                {
-                    continue
-                }
                 Event::Text(text) => {
-                    // TODO: use ByteString and only reallocate when something was escaped
-                    let value = Value::Text(match text.unescape()? {
-                        Cow::Borrowed(s) => xml
-                            .maybe_slice_ref(s.as_bytes())
-                            .try_into()
-                            .expect("string is checked by text.unescape() to be valid"),
+                    let len = children.push_text(match text.unescape()? {
+                        Cow::Borrowed(s) => xml.maybe_slice_ref(s.as_bytes()).try_into()?,
                         Cow::Owned(s) => s.into(),
                     });
-                    let _ = self.read_resolved_event()?;
-                    return Ok(value);
+                    self.check_text(len)?;
+                }
+                Event::CData(cdata) => {
+                    let cdata = xml.maybe_slice_ref(&cdata);
+                    std::str::from_utf8(&cdata)?;
+                    let len = children.push_text(cdata.try_into()?);
+                    self.check_text(len)?;
                 }
                 Event::Start(start) => {
                     let key = key(xml, &resolve_result, &start)?;
                     let start_name = xml.maybe_slice_ref(start.name().as_ref());
                     drop(resolve_result);
+                    let start_position = self.position;
+                    self.check_element(self.depth + 1)?;
+                    let attributes = self.read_attributes(xml, &start)?;
                     drop(start);
 
-                    map.insert_raw(key, self.read_into_value(xml)?);
+                    self.depth += 1;
+                    let value = self.read_into_value(xml)?.with_attributes(attributes);
+                    self.depth -= 1;
+                    children.push_element(key, value);
 
                     if !matches!(self.last(), Some(Event::End(end)) if end.name().as_ref() == start_name)
                     {
-                        return Err(XmlError::UnexpectedTag);
+                        // the element isn't closed
+                        return Err(XmlError::from(XmlErrorKind::UnexpectedTag(start_name))
+                            .at(|| Position::from_offset(xml, start_position)));
                     }
                 }
                 Event::Empty(tag) => {
-                    map.insert_raw(key(xml, &resolve_result, &tag)?, Value::Empty);
+                    let key = key(xml, &resolve_result, &tag)?;
+                    drop(resolve_result);
+                    self.check_element(self.depth + 1)?;
+                    let attributes = self.read_attributes(xml, &tag)?;
+
+                    children.push_element(key, Value::Empty.with_attributes(attributes));
                 }
                 Event::End(_) | Event::Eof => break,
                 Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => continue,
-                Event::CData(_) => todo!(),
             }
         }
 
-        Ok(Value::Map(map))
+        Ok(children.into_value())
+    }
+
+    fn read_attributes(
+        &self,
+        xml: &bytes::Bytes,
+        tag: &quick_xml::events::BytesStart<'x>,
+    ) -> Result<Attributes, XmlError> {
+        use quick_xml::name::ResolveResult;
+
+        let mut attributes = Attributes::new();
+        let mut namespaces = 0;
+
+        for attribute in tag.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            // namespace declarations are resolved by the reader
+            if attribute.key.as_namespace_binding().is_some() {
+                namespaces += 1;
+                if namespaces > self.limits.max_namespaces {
+                    return Err(limit_exceeded(Limit::Namespaces(
+                        self.limits.max_namespaces,
+                    )));
+                }
+                continue;
+            }
+            if attributes.len() >= self.limits.max_attributes {
+                return Err(limit_exceeded(Limit::Attributes(
+                    self.limits.max_attributes,
+                )));
+            }
+
+            let (resolve_result, local_name) = self.reader.resolve_attribute(attribute.key);
+            let namespace = match resolve_result {
+                ResolveResult::Bound(ns) => Some(xml.maybe_slice_ref(ns.as_ref()).try_into()?),
+                ResolveResult::Unbound => None,
+                ResolveResult::Unknown(prefix) => {
+                    return Err(XmlErrorKind::InvalidNamespace(xml.maybe_slice_ref(&prefix)).into())
+                }
+            };
+            let prefix = match attribute.key.prefix() {
+                Some(prefix) => Some(xml.maybe_slice_ref(prefix.as_ref()).try_into()?),
+                None => None,
+            };
+            let value: ByteString = match attribute.unescape_value()? {
+                Cow::Borrowed(s) => xml.maybe_slice_ref(s.as_bytes()).try_into()?,
+                Cow::Owned(s) => s.into(),
+            };
+
+            attributes.0.insert(
+                ElementName {
+                    namespace,
+                    prefix,
+                    local_name: xml.maybe_slice_ref(local_name.as_ref()).try_into()?,
+                },
+                value,
+            );
+        }
+
+        Ok(attributes)
     }
 }
 
@@ -127,6 +573,10 @@ mod tests {
 
     use super::*;
 
+    fn read_xml(xml: impl Into<bytes::Bytes>) -> Result<Value, XmlError> {
+        read_xml_with(xml, &ReadLimits::default())
+    }
+
     macro_rules! value_map {
         { $($key:expr => $value:expr),* $(,)? } => {
             ValueMap(crate::value::InnerValueMap::from([
@@ -150,6 +600,12 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn empty_pair() -> eyre::Result<()> {
+        assert_eq!(read_xml(r#"<foo></foo>"#)?, read_xml(r#"<foo/>"#)?);
+        Ok(())
+    }
+
     #[test]
     fn text() -> eyre::Result<()> {
         let xml = r#"<foo>bar</foo>"#;
@@ -192,4 +648,204 @@ mod tests {
         assert_eq!(value, read_xml(xml)?);
         Ok(())
     }
+
+    #[test]
+    fn attributes() -> eyre::Result<()> {
+        let xml = r#"<foo xmlns:x="urn:x" a="1" x:b="&amp;2" xml:lang="en"><bar c="3" /></foo>"#;
+
+        let value = read_xml(xml)?;
+        let foo = &value.to_map()?.0[0];
+        let attributes = foo.attributes().unwrap();
+
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(attributes.get(None, "a").unwrap(), "1");
+        assert_eq!(attributes.get(Some("urn:x"), "b").unwrap(), "&2");
+        assert_eq!(attributes.lang().unwrap(), "en");
+
+        let bar = &foo.to_map()?.0[0];
+        assert_eq!(bar.attributes().unwrap().get(None, "c").unwrap(), "3");
+        assert!(bar.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cdata() -> eyre::Result<()> {
+        let xml = r#"<foo>a <![CDATA[<b> & c]]> d</foo>"#;
+        let value = Value::Map(value_map! {
+            "foo" => Value::Text("a <b> & c d".into()),
+        });
+        assert_eq!(value, read_xml(xml)?);
+        Ok(())
+    }
+
+    #[test]
+    fn mixed() -> eyre::Result<()> {
+        let xml = r#"<foo>bar <baz>qux</baz> <![CDATA[quux]]></foo>"#;
+        let value = Value::Map(value_map! {
+            "foo" => Value::Mixed(vec![
+                Node::Text("bar ".into()),
+                Node::Element(
+                    ElementName { namespace: None, prefix: None, local_name: "baz".into() },
+                    Value::Text("qux".into()),
+                ),
+                Node::Text(" quux".into()),
+            ]),
+        });
+        assert_eq!(value, read_xml(xml)?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> eyre::Result<()> {
+        use crate::{write::XmlWriter, WriteOptions};
+
+        for xml in [
+            // a map would reorder the elements
+            r#"<ns0:p xmlns:ns0="urn:x"><ns0:a/><ns0:b/><ns0:a/></ns0:p>"#,
+            "<ns0:p xmlns:ns0=\"urn:x\">\n  <ns0:a/>\n  <ns0:b/>\n  <ns0:a/>\n</ns0:p>",
+            r#"<ns0:p xmlns:ns0="urn:x"> </ns0:p>"#,
+        ] {
+            let value = read_xml(xml)?;
+            assert!(value.to_map()?.0[0].is_mixed());
+
+            let (name, value) = value.to_map()?.0.first().unwrap();
+            let mut writer = XmlWriter::new(Vec::new(), &WriteOptions::new().declaration(false));
+            writer.write_document(name, value.clone())?;
+            assert_eq!(String::from_utf8(writer.into_inner())?, xml);
+        }
+
+        // whitespace between elements is insignificant
+        let value = read_xml("<p>\n  <a/>\n  <a/>\n  <b/>\n</p>")?;
+        assert!(value.to_map()?.0[0].is_map());
+        // whitespace without elements is an empty map
+        let value = read_xml("<p>\n</p>")?;
+        assert!(value.to_map()?.0[0].to_map()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(read_xml(r#"<foo>"#).is_err());
+        assert!(read_xml(r#"<foo></bar>"#).is_err());
+        assert!(read_xml(b"<foo><![CDATA[\xff]]></foo>".as_slice()).is_err());
+    }
+
+    #[test]
+    fn error_position() {
+        let position = |xml: &'static str| {
+            let position = read_xml(xml).unwrap_err().position().unwrap();
+            (position.line, position.column)
+        };
+        assert_eq!(position("<a>\n  <b></c>\n</a>"), (2, 6));
+        assert_eq!(position("<a>\n  <ä>\n  <b>"), (3, 3));
+        assert_eq!(position("<a>\n  <b/>"), (1, 1));
+    }
+
+    #[track_caller]
+    fn assert_limit(xml: impl Into<bytes::Bytes>, limits: &ReadLimits, limit: Limit) {
+        match read_xml_with(xml, limits) {
+            Err(XmlError {
+                kind: XmlErrorKind::LimitExceeded(exceeded),
+                ..
+            }) => assert_eq!(exceeded, limit),
+            result => panic!("expected {limit:?} to be exceeded, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn deep_nesting() -> eyre::Result<()> {
+        // would overflow the stack without a depth limit
+        let xml = "<a>".repeat(1_000_000);
+        assert_limit(xml, &ReadLimits::default(), Limit::Depth(128));
+
+        let limits = ReadLimits::new().max_depth(3);
+        read_xml_with("<a><b><c/></b></a>", &limits)?;
+        assert_limit("<a><b><c><d/></c></b></a>", &limits, Limit::Depth(3));
+        assert_limit("<a><b><c><d></d></c></b></a>", &limits, Limit::Depth(3));
+        Ok(())
+    }
+
+    #[test]
+    fn wide_fanout() -> eyre::Result<()> {
+        let xml = format!("<a>{}</a>", "<b/>".repeat(1_000_000));
+        assert_limit(xml, &ReadLimits::default(), Limit::Elements(100_000));
+
+        let limits = ReadLimits::new().max_elements(3);
+        read_xml_with("<a><b/><b></b></a>", &limits)?;
+        assert_limit("<a><b/><b/><b></b></a>", &limits, Limit::Elements(3));
+        Ok(())
+    }
+
+    #[test]
+    fn limits() -> eyre::Result<()> {
+        let limits = ReadLimits::new().max_size(16);
+        read_xml_with("<a>012345678</a>", &limits)?;
+        assert_limit("<a>0123456789</a>", &limits, Limit::Size(16));
+
+        // the limit applies to the unescaped and merged text
+        let limits = ReadLimits::new().max_text(4);
+        read_xml_with("<a>&lt;&lt;&lt;&lt;</a>", &limits)?;
+        assert_limit("<a>abc<![CDATA[de]]></a>", &limits, Limit::Text(4));
+
+        let limits = ReadLimits::new().max_attributes(2).max_namespaces(1);
+        read_xml_with(r#"<a xmlns="urn:a" b="1" c="2"/>"#, &limits)?;
+        assert_limit(r#"<a b="1" c="2" d="3"/>"#, &limits, Limit::Attributes(2));
+        assert_limit(
+            r#"<a xmlns="urn:a" xmlns:b="urn:b"/>"#,
+            &limits,
+            Limit::Namespaces(1),
+        );
+
+        let xml = format!("<a>{}</a>", "<b/>".repeat(200_000));
+        read_xml_with(xml, &ReadLimits::unlimited())?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    #[test]
+    fn unsupported_encoding() -> eyre::Result<()> {
+        #[track_caller]
+        fn assert_unsupported(xml: &'static [u8], expected: &str) {
+            match read_xml(xml) {
+                Err(XmlError {
+                    kind: XmlErrorKind::UnsupportedEncoding(label),
+                    ..
+                }) => assert_eq!(label, expected),
+                result => panic!("expected an unsupported encoding, got {result:?}"),
+            }
+        }
+
+        assert_unsupported(
+            b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><a>\xe9</a>",
+            "ISO-8859-1",
+        );
+        // valid UTF-8 that would be read as the wrong characters
+        assert_unsupported(
+            "<?xml version='1.0' encoding='windows-1252'?><a>é</a>".as_bytes(),
+            "windows-1252",
+        );
+        assert_unsupported(b"\xff\xfe<\0a\0/\0>\0", "UTF-16LE");
+        assert_unsupported(b"\0<\0?\0x\0m\0l", "UTF-16BE");
+
+        read_xml(r#"<?xml version="1.0" encoding="UTF-8"?><a/>"#)?;
+        read_xml(r#"<?xml version="1.0"?><a/>"#)?;
+        Ok(())
+    }
+
+    #[test]
+    fn fragmented_text() -> eyre::Result<()> {
+        // text that is split by comments is merged without copying it for
+        // each fragment
+        let xml = format!("<a>{}</a>", "x<!---->".repeat(500_000));
+        let value = read_xml(xml)?;
+        assert_eq!(value.to_map()?.0[0].to_text()?.len(), 500_000);
+
+        let limits = ReadLimits::new().max_text(1000);
+        read_xml_with(format!("<a>{}</a>", "x<!---->".repeat(1000)), &limits)?;
+        let xml = format!("<a>{}</a>", "x<!---->".repeat(1001));
+        assert_limit(xml, &limits, Limit::Text(1000));
+        Ok(())
+    }
 }
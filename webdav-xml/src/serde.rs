@@ -0,0 +1,973 @@
+// SPDX-FileCopyrightText: d-k-bo <d-k-bo@mailbox.org>
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Conversion between [`Value`] and types implementing [`serde`]'s
+//! `Deserialize` and `Serialize` traits.
+//!
+//! This is useful for custom properties that don't need the full control of
+//! a manual [`TryFrom<&Value>`] implementation:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use webdav_xml::{elements::Properties, ElementName, FromXml};
+//!
+//! #[derive(Debug, PartialEq, Deserialize, Serialize)]
+//! struct ShareTypes {
+//!     #[serde(rename = "share-type", default)]
+//!     share_types: Vec<u8>,
+//! }
+//!
+//! let xml = r#"
+//! <d:prop xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+//!   <oc:share-types>
+//!     <oc:share-type>0</oc:share-type>
+//!     <oc:share-type>3</oc:share-type>
+//!   </oc:share-types>
+//! </d:prop>
+//! "#;
+//! let prop = Properties::from_xml(xml)?;
+//!
+//! assert_eq!(
+//!     prop.get_serde::<ShareTypes>(Some("http://owncloud.org/ns"), "share-types")
+//!         .transpose()?,
+//!     Some(ShareTypes { share_types: vec![0, 3] })
+//! );
+//!
+//! let prop = Properties::new().with_serde(
+//!     ElementName::new("http://owncloud.org/ns", "share-types").with_prefix("oc"),
+//!     &ShareTypes { share_types: vec![0, 3] },
+//! )?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The following conventions are used:
+//!
+//! - Text is parsed into strings, numbers, booleans (`true`/`false` or
+//!   `1`/`0`) and unit enum variants. Surrounding whitespace is ignored for
+//!   everything but strings.
+//! - Child elements are mapped to struct fields or map entries by their local
+//!   name. When serializing, they are put into the namespace of the parent
+//!   element.
+//! - Repeated child elements are mapped to sequences. A single or missing
+//!   child element is also accepted for a sequence, but a missing one
+//!   requires `#[serde(default)]`.
+//! - Empty elements are mapped to `()`, unit structs, `None` and empty
+//!   sequences, maps and structs. `None` fields and empty sequence fields
+//!   are left out when serializing.
+//! - Enum variants are read from text or from a single child element named
+//!   after the variant and written as the latter, e.g. `<d:exclusive/>`.
+//! - Attributes are ignored and mixed content is rejected.
+
+use std::fmt;
+
+use ::serde::{
+    de::{self, value::BorrowedStrDeserializer, IntoDeserializer},
+    ser,
+};
+use bytestring::ByteString;
+use nonempty::NonEmpty;
+
+use crate::{value::ValueMap, ElementName, ExtractElementError, ExtractElementErrorKind, Value};
+
+/// Deserialize an instance of `T` from a [`Value`].
+pub fn from_value<'v, T>(value: &'v Value) -> Result<T, ExtractElementError>
+where
+    T: de::Deserialize<'v>,
+{
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Serialize `value` into a [`Value`] without a namespace for child elements.
+///
+/// Use [`Serializer::for_element()`] to put child elements into a namespace.
+pub fn to_value<T>(value: &T) -> Result<Value, SerializeError>
+where
+    T: ser::Serialize + ?Sized,
+{
+    value.serialize(Serializer::new())
+}
+
+impl de::Error for ExtractElementError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::other(msg.to_string())
+    }
+}
+
+/// A [`serde::Deserializer`](de::Deserializer) that reads from a [`Value`].
+#[derive(Clone, Copy, Debug)]
+pub struct Deserializer<'v> {
+    value: &'v Value,
+}
+
+impl<'v> Deserializer<'v> {
+    pub fn new(value: &'v Value) -> Self {
+        Self {
+            value: value.content(),
+        }
+    }
+
+    fn invalid_type(&self, expected: &'static str) -> ExtractElementError {
+        ExtractElementError::new(ExtractElementErrorKind::InvalidValueType {
+            expected,
+            got: self.value.value_type(),
+        })
+    }
+    fn text(&self) -> Result<&'v str, ExtractElementError> {
+        match self.value {
+            Value::Empty => Ok(""),
+            Value::Text(text) => Ok(text),
+            _ => Err(self.invalid_type("text")),
+        }
+    }
+    fn parse<T>(&self) -> Result<T, ExtractElementError>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.text()?
+            .trim()
+            .parse()
+            .map_err(ExtractElementError::other)
+    }
+    fn items(&self) -> Vec<&'v Value> {
+        match self.value {
+            Value::Empty => Vec::new(),
+            Value::List(list) => list.iter().collect(),
+            value => vec![value],
+        }
+    }
+}
+
+macro_rules! deserialize_parse {
+    ($($deserialize:ident => $visit:ident,)*) => {
+        $(
+            fn $deserialize<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'v> de::Deserializer<'v> for Deserializer<'v> {
+    type Error = ExtractElementError;
+
+    fn deserialize_any<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Empty => visitor.visit_unit(),
+            Value::Text(text) => visitor.visit_borrowed_str(text),
+            Value::Map(_) => self.deserialize_map(visitor),
+            Value::List(_) => self.deserialize_seq(visitor),
+            Value::Mixed(_) | Value::Attributed(_) => Err(self.invalid_type("text, list or map")),
+        }
+    }
+
+    fn deserialize_bool<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.text()?.trim() {
+            "true" | "1" => visitor.visit_bool(true),
+            "false" | "0" => visitor.visit_bool(false),
+            text => Err(de::Error::invalid_value(
+                de::Unexpected::Str(text),
+                &"`true`, `false`, `1` or `0`",
+            )),
+        }
+    }
+
+    deserialize_parse! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.text()?)
+    }
+    fn deserialize_string<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+    fn deserialize_bytes<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.text()?.as_bytes())
+    }
+    fn deserialize_byte_buf<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Empty => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+    fn deserialize_unit<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Empty => visitor.visit_unit(),
+            Value::Map(map) if map.is_empty() => visitor.visit_unit(),
+            _ => Err(self.invalid_type("empty")),
+        }
+    }
+    fn deserialize_unit_struct<V: de::Visitor<'v>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+    fn deserialize_newtype_struct<V: de::Visitor<'v>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut seq =
+            de::value::SeqDeserializer::new(self.items().into_iter().map(Deserializer::new));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+    fn deserialize_tuple<V: de::Visitor<'v>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple_struct<V: de::Visitor<'v>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: de::Visitor<'v>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let entries = match self.value {
+            Value::Empty => None,
+            Value::Map(map) => Some(map.iter()),
+            _ => return Err(self.invalid_type("map")),
+        };
+        let mut map = de::value::MapDeserializer::new(
+            entries
+                .into_iter()
+                .flatten()
+                .map(|(name, value)| (&*name.local_name, Deserializer::new(value))),
+        );
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+    fn deserialize_struct<V: de::Visitor<'v>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'v>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Text(text) => visitor.visit_enum(BorrowedStrDeserializer::new(text.trim())),
+            Value::Map(map) if map.len() == 1 => {
+                let (name, value) = map.iter().next().expect("map has one entry");
+                visitor.visit_enum(Variant {
+                    name: &name.local_name,
+                    value: Deserializer::new(value),
+                })
+            }
+            _ => Err(self.invalid_type("text or a single element")),
+        }
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'v>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+    fn deserialize_ignored_any<V: de::Visitor<'v>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+impl<'v> IntoDeserializer<'v, ExtractElementError> for Deserializer<'v> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct Variant<'v> {
+    name: &'v str,
+    value: Deserializer<'v>,
+}
+
+impl<'v> de::EnumAccess<'v> for Variant<'v> {
+    type Error = ExtractElementError;
+    type Variant = Deserializer<'v>;
+
+    fn variant_seed<V: de::DeserializeSeed<'v>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.name))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'v> de::VariantAccess<'v> for Deserializer<'v> {
+    type Error = ExtractElementError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(self)
+    }
+    fn newtype_variant_seed<T: de::DeserializeSeed<'v>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: de::Visitor<'v>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+    fn struct_variant<V: de::Visitor<'v>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// Returned when serializing into a [`Value`] fails.
+#[derive(Debug)]
+pub struct SerializeError(ErrorKind);
+
+#[derive(Debug)]
+enum ErrorKind {
+    Custom(String),
+    /// A `None` or an empty sequence that should be left out by the
+    /// surrounding struct, map or sequence.
+    Skip,
+}
+
+impl SerializeError {
+    fn skip() -> Self {
+        Self(ErrorKind::Skip)
+    }
+    fn is_skip(&self) -> bool {
+        matches!(self.0, ErrorKind::Skip)
+    }
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            ErrorKind::Custom(msg) => f.write_str(msg),
+            ErrorKind::Skip => f.write_str("unexpected empty value"),
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(ErrorKind::Custom(msg.to_string()))
+    }
+}
+
+/// A [`serde::Serializer`](ser::Serializer) that produces a [`Value`].
+#[derive(Clone, Debug, Default)]
+pub struct Serializer {
+    namespace: Option<ByteString>,
+    prefix: Option<ByteString>,
+    nested: bool,
+}
+
+impl Serializer {
+    /// Create a serializer that doesn't use a namespace for child elements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Create a serializer that puts child elements into the namespace of
+    /// the element `name`.
+    pub fn for_element(name: &ElementName<ByteString>) -> Self {
+        Self {
+            namespace: name.namespace.clone(),
+            prefix: name.namespace.as_ref().and(name.prefix.clone()),
+            nested: false,
+        }
+    }
+
+    fn nested(&self) -> Self {
+        Self {
+            nested: true,
+            ..self.clone()
+        }
+    }
+    fn name(&self, local_name: impl Into<ByteString>) -> ElementName<ByteString> {
+        ElementName {
+            namespace: self.namespace.clone(),
+            prefix: self.prefix.clone(),
+            local_name: local_name.into(),
+        }
+    }
+    fn variant(&self, variant: &'static str, value: Value) -> Value {
+        let mut map = ValueMap::new();
+        map.insert_by_name(self.name(variant), value);
+        Value::Map(map)
+    }
+    fn skip(&self) -> Result<Value, SerializeError> {
+        match self.nested {
+            true => Err(SerializeError::skip()),
+            false => Ok(Value::Empty),
+        }
+    }
+}
+
+/// Serializes `value` as a child, returning `None` if it should be left out.
+fn serialize_child<T>(serializer: &Serializer, value: &T) -> Result<Option<Value>, SerializeError>
+where
+    T: ser::Serialize + ?Sized,
+{
+    match value.serialize(serializer.nested()) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_skip() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+macro_rules! serialize_display {
+    ($($serialize:ident: $ty:ty,)*) => {
+        $(
+            fn $serialize(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(Value::Text(v.to_string().into()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeVariant<SerializeSeq>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeVariant<SerializeMap>;
+
+    serialize_display! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Text(v.into()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.serialize_str(v),
+            Err(e) => Err(ser::Error::custom(e)),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.skip()
+    }
+    fn serialize_some<T: ser::Serialize + ?Sized>(self, v: &T) -> Result<Self::Ok, Self::Error> {
+        v.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Empty)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Empty)
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(self.variant(variant, Value::Empty))
+    }
+    fn serialize_newtype_struct<T: ser::Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        v: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        v.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ser::Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        v: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value = serialize_child(&self, v)?.unwrap_or_default();
+        Ok(self.variant(variant, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeSeq {
+            serializer: self,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeVariant {
+            serializer: self.clone(),
+            variant,
+            inner: self.nested().serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeMap {
+            serializer: self,
+            map: ValueMap::new(),
+            key: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeVariant {
+            serializer: self.clone(),
+            variant,
+            inner: self.nested().serialize_map(Some(len))?,
+        })
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeSeq {
+    serializer: Serializer,
+    items: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_element<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        if let Some(value) = serialize_child(&self.serializer, v)? {
+            self.items.push(value);
+        }
+        Ok(())
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        match NonEmpty::from_vec(self.items) {
+            None => self.serializer.skip(),
+            Some(items) if items.tail.is_empty() => Ok(items.head),
+            Some(items) => Ok(Value::List(Box::new(items))),
+        }
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_element<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeMap {
+    serializer: Serializer,
+    map: ValueMap,
+    key: Option<ByteString>,
+}
+
+impl SerializeMap {
+    fn insert<T>(&mut self, key: impl Into<ByteString>, v: &T) -> Result<(), SerializeError>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        if let Some(value) = serialize_child(&self.serializer, v)? {
+            self.map.insert_by_name(self.serializer.name(key), value);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_key<T: ser::Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        match key.serialize(Serializer::new())? {
+            Value::Text(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            _ => Err(ser::Error::custom("map keys must be strings or numbers")),
+        }
+    }
+    fn serialize_value<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value without a key"))?;
+        self.insert(key, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Map(self.map))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        v: &T,
+    ) -> Result<(), Self::Error> {
+        self.insert(key, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Map(self.map))
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeVariant<S> {
+    serializer: Serializer,
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> SerializeVariant<S> {
+    fn wrap(
+        serializer: &Serializer,
+        variant: &'static str,
+        value: Result<Value, SerializeError>,
+    ) -> Result<Value, SerializeError> {
+        let value = match value {
+            Err(e) if e.is_skip() => Value::Empty,
+            value => value?,
+        };
+        Ok(serializer.variant(variant, value))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeSeq> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let value = ser::SerializeSeq::end(self.inner);
+        Self::wrap(&self.serializer, self.variant, value)
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeMap> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        v: &T,
+    ) -> Result<(), Self::Error> {
+        self.inner.insert(key, v)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let value = ser::SerializeStruct::end(self.inner);
+        Self::wrap(&self.serializer, self.variant, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{elements::Properties, FromXml, IntoXml};
+
+    const OC: &str = "http://owncloud.org/ns";
+
+    #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+    struct ShareTypes {
+        #[serde(rename = "share-type", default)]
+        share_types: Vec<u8>,
+    }
+
+    #[test]
+    fn repeated() -> eyre::Result<()> {
+        let xml = r#"
+<d:prop xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <oc:share-types>
+    <oc:share-type>0</oc:share-type>
+    <oc:share-type>3</oc:share-type>
+  </oc:share-types>
+</d:prop>
+"#;
+        let share_types = ShareTypes {
+            share_types: vec![0, 3],
+        };
+
+        let prop = Properties::from_xml(xml)?;
+        assert_eq!(
+            prop.get_serde::<ShareTypes>(Some(OC), "share-types")
+                .transpose()?,
+            Some(ShareTypes {
+                share_types: vec![0, 3]
+            })
+        );
+        assert!(prop.get_serde::<ShareTypes>(None, "share-types").is_none());
+
+        let prop = Properties::new().with_serde(
+            ElementName::new(OC, "share-types").with_prefix("oc"),
+            &share_types,
+        )?;
+        assert_eq!(
+            std::str::from_utf8(&prop.into_xml()?)?,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <oc:share-types>
+    <oc:share-type>0</oc:share-type>
+    <oc:share-type>3</oc:share-type>
+  </oc:share-types>
+</d:prop>"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_and_empty() -> eyre::Result<()> {
+        let value = Value::from_xml(r#"<x><share-type>1</share-type></x>"#)?;
+        let value = value.to_map()?.get_by_name(None, "x").unwrap();
+        assert_eq!(
+            from_value::<ShareTypes>(value)?,
+            ShareTypes {
+                share_types: vec![1]
+            }
+        );
+
+        assert_eq!(
+            from_value::<ShareTypes>(&Value::Empty)?,
+            ShareTypes::default()
+        );
+        assert_eq!(
+            from_value::<ShareTypes>(&Value::Map(ValueMap::new()))?,
+            ShareTypes::default()
+        );
+        assert_eq!(
+            to_value(&ShareTypes::default())?,
+            Value::Map(ValueMap::new())
+        );
+
+        Ok(())
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "kebab-case")]
+    enum Scope {
+        Exclusive,
+        Shared,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Quota {
+        used_bytes: u64,
+        available_bytes: Option<i64>,
+        favorite: bool,
+        comment: Option<String>,
+        scope: Scope,
+        owner: Owner,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Owner {
+        name: String,
+        id: Option<u32>,
+    }
+
+    #[test]
+    fn scalars() -> eyre::Result<()> {
+        let xml = r#"
+<ex:quota xmlns:ex="http://example.com/ns">
+  <ex:used-bytes> 1024 </ex:used-bytes>
+  <ex:favorite>1</ex:favorite>
+  <ex:comment/>
+  <ex:scope><ex:shared/></ex:scope>
+  <ex:owner><ex:name> Jane Smith </ex:name></ex:owner>
+</ex:quota>
+"#;
+        let value = Value::from_xml(xml)?;
+        let value = value
+            .to_map()?
+            .get_by_name(Some("http://example.com/ns"), "quota")
+            .unwrap();
+        let quota = from_value::<Quota>(value)?;
+        assert_eq!(
+            quota,
+            Quota {
+                used_bytes: 1024,
+                available_bytes: None,
+                favorite: true,
+                comment: None,
+                scope: Scope::Shared,
+                owner: Owner {
+                    name: " Jane Smith ".into(),
+                    id: None
+                },
+            }
+        );
+
+        let value = to_value(&quota)?;
+        assert_eq!(
+            value.to_map()?.get_by_name(None, "favorite"),
+            Some(&Value::Text("true".into()))
+        );
+        assert!(value.to_map()?.get_by_name(None, "comment").is_none());
+        assert_eq!(from_value::<Quota>(&value)?, quota);
+
+        // unit variants can also be given as text
+        assert_eq!(
+            from_value::<Scope>(&Value::Text(" exclusive ".into()))?,
+            Scope::Exclusive
+        );
+
+        Ok(())
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    enum Variants {
+        Unit,
+        Newtype(String),
+        Tuple(u8, u8),
+        Struct { a: Option<u8>, b: Vec<u8> },
+    }
+
+    #[test]
+    fn enums() -> eyre::Result<()> {
+        for variant in [
+            Variants::Unit,
+            Variants::Newtype("a".into()),
+            Variants::Tuple(1, 2),
+            Variants::Struct {
+                a: Some(1),
+                b: vec![2, 3],
+            },
+        ] {
+            let value = to_value(&variant)?;
+            assert_eq!(value.to_map()?.len(), 1);
+            assert_eq!(from_value::<Variants>(&value)?, variant);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> eyre::Result<()> {
+        assert!(from_value::<u8>(&Value::Text("256".into())).is_err());
+        assert!(from_value::<bool>(&Value::Text("yes".into())).is_err());
+        assert!(from_value::<Quota>(&Value::Text("quota".into())).is_err());
+
+        let value = Value::from_xml(r#"<x>text <name>a</name></x>"#)?;
+        let value = value.to_map()?.get_by_name(None, "x").unwrap();
+        assert!(from_value::<Owner>(value).is_err());
+
+        let map = std::collections::BTreeMap::from([((1, 2), 3)]);
+        assert!(to_value(&map).is_err());
+
+        Ok(())
+    }
+}
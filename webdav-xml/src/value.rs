@@ -2,13 +2,15 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use std::sync::OnceLock;
+
 use bytestring::ByteString;
 use indexmap::IndexMap;
 use nonempty::{nonempty, NonEmpty};
 
 use crate::{
     element::{Element, ElementExt, ElementName},
-    ExtractElementError, ExtractElementErrorKind,
+    Attributes, ExtractElementError, ExtractElementErrorKind,
 };
 
 /// Represents the content of an XML element.
@@ -29,12 +31,63 @@ pub enum Value {
     /// The parent element contains multiple elements of this type, e.g. `<foo
     /// /><foo />`
     List(Box<NonEmpty<Value>>),
+    /// The element contains text interleaved with other elements, e.g.
+    /// `<foo>bar <baz /></foo>`
+    ///
+    /// Unlike [`Value::Map`], this preserves the order of all nodes and the
+    /// whitespace between them. Elements without text are read as mixed
+    /// content too if a map would reorder them, e.g. `<foo><a/><b/><a/></foo>`,
+    /// or if they only contain whitespace.
+    Mixed(Vec<Node>),
+    /// The element has attributes, e.g. `<foo xml:lang="en">bar</foo>`
+    ///
+    /// The wrapped value is never a [`Value::List`] or another
+    /// [`Value::Attributed`]. Use [`Value::with_attributes()`] to construct it.
+    Attributed(Box<(Attributes, Value)>),
 }
 
 impl Value {
-    #[track_caller]
-    pub fn to_text(&self) -> Result<&ByteString, ExtractElementError> {
+    /// Attach attributes to this value.
+    ///
+    /// Existing attributes are replaced. If `attributes` is empty, they are
+    /// removed instead. Attributes belong to a single element, so they are
+    /// attached to each element of a [`Value::List`].
+    pub fn with_attributes(self, attributes: Attributes) -> Self {
+        let value = match self {
+            Value::Attributed(attributed) => attributed.1,
+            Value::List(list) => {
+                return Value::List(Box::new(
+                    list.map(|value| value.with_attributes(attributes.clone())),
+                ))
+            }
+            value => value,
+        };
+        if attributes.is_empty() {
+            value
+        } else {
+            Value::Attributed(Box::new((attributes, value)))
+        }
+    }
+    /// The attributes of this element, if it has any.
+    pub fn attributes(&self) -> Option<&Attributes> {
+        match self {
+            Self::Attributed(attributed) => Some(&attributed.0),
+            _ => None,
+        }
+    }
+    /// The content of this element without its attributes.
+    ///
+    /// All other accessors like [`Value::to_text()`] already ignore
+    /// attributes.
+    pub fn content(&self) -> &Value {
         match self {
+            Self::Attributed(attributed) => &attributed.1,
+            value => value,
+        }
+    }
+
+    pub fn to_text(&self) -> Result<&ByteString, ExtractElementError> {
+        match self.content() {
             Self::Text(s) => Ok(s),
             _ => Err(ExtractElementError::new(
                 ExtractElementErrorKind::InvalidValueType {
@@ -45,10 +98,16 @@ impl Value {
         }
     }
 
-    #[track_caller]
+    /// Whitespace without child elements, e.g. `<d:prop>\n</d:prop>`, is
+    /// read as [mixed content](Value::Mixed) and returned as an empty map.
     pub fn to_map(&self) -> Result<&ValueMap, ExtractElementError> {
-        match self {
+        static EMPTY: OnceLock<ValueMap> = OnceLock::new();
+
+        match self.content() {
             Self::Map(map) => Ok(map),
+            Self::Mixed(nodes) if nodes.iter().all(Node::is_whitespace) => {
+                Ok(EMPTY.get_or_init(ValueMap::new))
+            }
             _ => Err(ExtractElementError::new(
                 ExtractElementErrorKind::InvalidValueType {
                     expected: "map",
@@ -58,7 +117,18 @@ impl Value {
         }
     }
 
-    #[track_caller]
+    pub fn to_mixed(&self) -> Result<&[Node], ExtractElementError> {
+        match self.content() {
+            Self::Mixed(nodes) => Ok(nodes),
+            _ => Err(ExtractElementError::new(
+                ExtractElementErrorKind::InvalidValueType {
+                    expected: "mixed",
+                    got: self.value_type(),
+                },
+            )),
+        }
+    }
+
     pub fn to_list(&self) -> Result<&NonEmpty<Value>, ExtractElementError> {
         match self {
             Self::List(list) => Ok(list),
@@ -72,37 +142,83 @@ impl Value {
     }
 
     pub fn is_empty(&self) -> bool {
-        matches!(self, Self::Empty)
+        matches!(self.content(), Self::Empty)
     }
 
     pub fn is_text(&self) -> bool {
-        matches!(self, Self::Text(_))
+        matches!(self.content(), Self::Text(_))
     }
 
     pub fn is_map(&self) -> bool {
-        matches!(self, Self::Map(_))
+        matches!(self.content(), Self::Map(_))
+    }
+
+    pub fn is_mixed(&self) -> bool {
+        matches!(self.content(), Self::Mixed(_))
     }
 
     pub fn is_list(&self) -> bool {
         matches!(self, Self::List(_))
     }
 
-    fn value_type(&self) -> &'static str {
+    pub(crate) fn value_type(&self) -> &'static str {
         match self {
             Value::Empty => "empty",
             Value::Text(..) => "text",
             Value::Map(..) => "map",
             Value::List(..) => "list",
+            Value::Mixed(..) => "mixed",
+            Value::Attributed(attributed) => attributed.1.value_type(),
         }
     }
 }
 
+/// A node of an element with [mixed content](Value::Mixed).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    /// A text node, including CDATA sections
+    Text(ByteString),
+    /// A child element and its content
+    Element(ElementName<ByteString>, Value),
+}
+
+impl Node {
+    /// The name of the element, or `None` for a text node.
+    pub fn name(&self) -> Option<&ElementName<ByteString>> {
+        match self {
+            Self::Text(_) => None,
+            Self::Element(name, _) => Some(name),
+        }
+    }
+    fn is_whitespace(&self) -> bool {
+        matches!(self, Self::Text(text) if text.chars().all(char::is_whitespace))
+    }
+}
+
 impl From<String> for Value {
     fn from(s: String) -> Self {
         Value::Text(s.into())
     }
 }
 
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.into())
+    }
+}
+
+impl From<ByteString> for Value {
+    fn from(s: ByteString) -> Self {
+        Value::Text(s)
+    }
+}
+
+impl From<ValueMap> for Value {
+    fn from(map: ValueMap) -> Self {
+        Value::Map(map)
+    }
+}
+
 pub(crate) type InnerValueMap = IndexMap<ElementName<ByteString>, Value>;
 
 /// A mapping from tag names to [`Value`]s.
@@ -126,7 +242,7 @@ impl ValueMap {
     {
         self.0
             .get(&E::element_name::<&'static str>())
-            .map(E::try_from)
+            .map(|value| E::try_from(value).map_err(|e| e.within(E::element_name(), None)))
     }
     /// Extract a non-empty child element of a specific type.
     ///
@@ -144,22 +260,76 @@ impl ValueMap {
     {
         self.0
             .get(&E::element_name::<&'static str>())
-            .map(|value| match value {
-                Value::Empty => None,
-                v => Some(v.try_into()),
+            .map(|value| match value.is_empty() {
+                true => None,
+                false => Some(E::try_from(value).map_err(|e| e.within(E::element_name(), None))),
             })
     }
+    /// Read the attributes of a child element of a specific type.
+    ///
+    /// If the element occurs multiple times, the attributes of the first
+    /// occurrence are returned.
+    pub fn attributes<E: Element>(&self) -> Option<&Attributes> {
+        match self.0.get(&E::element_name::<&'static str>())? {
+            Value::List(list) => list.first().attributes(),
+            value => value.attributes(),
+        }
+    }
     /// Insert a child value into the map.
     pub fn insert<E: Element>(&mut self, value: Value) {
         let key = E::element_name();
-        self.insert_raw(key, value)
+        self.insert_by_name(key, value)
     }
-}
-
-impl ValueMap {
-    pub(crate) fn iter_all<'v, E>(
-        &'v self,
-    ) -> impl Iterator<Item = Result<E, ExtractElementError>> + 'v
+    /// Insert a child value with a name that is only known at runtime.
+    ///
+    /// Like [`insert`](Self::insert), inserting a name that already exists
+    /// adds another element with that name.
+    pub fn insert_by_name(&mut self, name: ElementName<ByteString>, value: Value) {
+        match self.0.get_mut(&name) {
+            Some(Value::List(list)) => list.push(value),
+            Some(old_value) => {
+                *old_value = Value::List(Box::new(nonempty![std::mem::take(old_value), value]));
+            }
+            None => {
+                self.0.insert(name, value);
+            }
+        }
+    }
+    /// Read the value of a child element by its namespace and local name.
+    ///
+    /// If the element occurs multiple times, a [`Value::List`] is returned.
+    pub fn get_by_name(&self, namespace: Option<&str>, local_name: &str) -> Option<&Value> {
+        self.0.get(&ElementName {
+            namespace,
+            prefix: None,
+            local_name,
+        })
+    }
+    /// Remove all child elements with the given namespace and local name.
+    pub fn remove_by_name(&mut self, namespace: Option<&str>, local_name: &str) -> Option<Value> {
+        self.0.shift_remove(&ElementName {
+            namespace,
+            prefix: None,
+            local_name,
+        })
+    }
+    /// Iterate over the names and values of all child elements.
+    ///
+    /// Elements that occur multiple times are yielded once as a
+    /// [`Value::List`].
+    pub fn iter(&self) -> impl Iterator<Item = (&ElementName<ByteString>, &Value)> {
+        self.0.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Extract all child elements of a specific type.
+    ///
+    /// Yields nothing if the element doesn't exist.
+    pub fn iter_all<'v, E>(&'v self) -> impl Iterator<Item = Result<E, ExtractElementError>> + 'v
     where
         E: Element + TryFrom<&'v Value, Error = ExtractElementError> + 'v,
     {
@@ -181,13 +351,18 @@ impl ValueMap {
             }
         }
 
-        match self.0.get(&E::element_name::<&'static str>()) {
-            Some(Value::List(list)) => ElementIter::List(list.iter()),
-            Some(value) => ElementIter::Single(std::iter::once(value)),
-            None => ElementIter::Empty,
-        }
-        .map(E::try_from)
+        let (iter, is_list) = match self.0.get(&E::element_name::<&'static str>()) {
+            Some(Value::List(list)) => (ElementIter::List(list.iter()), true),
+            Some(value) => (ElementIter::Single(std::iter::once(value)), false),
+            None => (ElementIter::Empty, false),
+        };
+        iter.enumerate().map(move |(i, value)| {
+            E::try_from(value).map_err(|e| e.within(E::element_name(), is_list.then_some(i + 1)))
+        })
     }
+}
+
+impl ValueMap {
     // pub(crate) fn iter_all_nonempty<'v, E>(&'v self) -> impl Iterator<Item =
     // Result<E, Error>> + 'v where
     //     E: Element + TryFrom<&'v Value, Error = Error>,
@@ -215,17 +390,6 @@ impl ValueMap {
     //     .transpose()
     //     .ok_or(Error::MissingElement(E::LOCAL_NAME))?
     // }
-    pub(crate) fn insert_raw(&mut self, key: ElementName<ByteString>, value: Value) {
-        match self.0.get_mut(&key) {
-            Some(Value::List(list)) => list.push(value),
-            Some(old_value) => {
-                *old_value = Value::List(Box::new(nonempty![std::mem::take(old_value), value]));
-            }
-            None => {
-                self.0.insert(key, value);
-            }
-        }
-    }
 }
 
 impl AsRef<InnerValueMap> for ValueMap {
@@ -245,3 +409,30 @@ impl From<InnerValueMap> for ValueMap {
         Self(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nonempty::nonempty;
+
+    use super::*;
+
+    #[test]
+    fn with_attributes() {
+        let mut attributes = Attributes::new();
+        attributes.set_lang("en");
+
+        let list = Value::List(Box::new(nonempty![Value::Text("a".into()), Value::Empty]));
+        let Value::List(list) = list.with_attributes(attributes.clone()) else {
+            panic!("the list is preserved")
+        };
+        for value in list.iter() {
+            assert_eq!(value.attributes(), Some(&attributes));
+        }
+
+        let value = Value::Text("a".into()).with_attributes(attributes);
+        assert_eq!(
+            value.with_attributes(Attributes::new()),
+            Value::Text("a".into())
+        );
+    }
+}
@@ -2,56 +2,380 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::{borrow::Cow, collections::HashMap};
+use std::borrow::Cow;
 
 use bytestring::ByteString;
+use indexmap::IndexMap;
 
 use crate::{
     element::{Element, ElementExt, ElementName},
-    Value, XmlError,
+    value::Node,
+    Attributes, Value, XmlError, XML_NAMESPACE, XML_PREFIX,
 };
 
 pub(crate) fn write_xml<E: Element>(
     writer: impl std::io::Write,
     value: Value,
+    options: &WriteOptions,
 ) -> Result<(), XmlError> {
-    let mut writer = XmlWriter {
-        inner: quick_xml::Writer::new_with_indent(writer, b' ', 2),
-        namespaces: HashMap::new(),
-    };
-    writer.inner.write_event(quick_xml::events::Event::Decl(
-        quick_xml::events::BytesDecl::new("1.0", Some("utf-8"), None),
-    ))?;
+    XmlWriter::new(writer, options).write_document(&E::element_name(), value)
+}
+
+/// Options for writing XML, used by
+/// [`IntoXml::write_xml_with`](crate::IntoXml::write_xml_with).
+///
+/// ```
+/// use webdav_xml::{elements::Properties, properties::DisplayName, IntoXml, WriteOptions};
+///
+/// let options = WriteOptions::new().prefix("DAV:", "D");
+/// let xml = Properties::new()
+///     .with(DisplayName("Example".into()))
+///     .into_xml_with(&options)?;
+/// assert!(std::str::from_utf8(&xml)?.contains("<D:displayname>Example</D:displayname>"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// By default, the output is indented by two spaces and starts with an XML
+/// declaration. Use [`compact`](Self::compact) to reduce the size of large
+/// responses and [`declaration`](Self::declaration) to write fragments that
+/// are embedded into another document:
+///
+/// ```
+/// use webdav_xml::{
+///     elements::{Condition, DavError},
+///     IntoXml, WriteOptions,
+/// };
+///
+/// let options = WriteOptions::new().compact().declaration(false);
+/// let xml = DavError::new(Condition::PropfindFiniteDepth).into_xml_with(&options)?;
+/// assert_eq!(
+///     xml,
+///     r#"<d:error xmlns:d="DAV:"><d:propfind-finite-depth/></d:error>"#
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct WriteOptions {
+    prefixes: IndexMap<ByteString, ByteString>,
+    local_namespaces: bool,
+    indent: Option<(u8, usize)>,
+    line_ending: LineEnding,
+    declaration: bool,
+    self_closing: SelfClosing,
+    escape: Escape,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            prefixes: IndexMap::new(),
+            local_namespaces: false,
+            indent: Some((b' ', 2)),
+            line_ending: LineEnding::default(),
+            declaration: true,
+            self_closing: SelfClosing::default(),
+            escape: Escape::default(),
+        }
+    }
+}
 
-    let name = E::element_name();
-    writer.resolve_namespaces(&name, &value);
-    writer.write_toplevel(&name, value)
+/// The line ending used by [`WriteOptions`] between indented elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
 }
 
-struct XmlWriter<W>
+impl LineEnding {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// How [`WriteOptions`] writes elements without content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfClosing {
+    /// `<d:collection/>`
+    #[default]
+    Compact,
+    /// `<d:collection />`, which some old XML parsers require
+    Spaced,
+    /// `<d:collection></d:collection>`
+    Never,
+}
+
+/// Which characters [`WriteOptions`] escapes in text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Escape {
+    /// Only escape `<`, `>` and `&`, which is enough for text content.
+    #[default]
+    Partial,
+    /// Also escape `"` and `'`.
+    Full,
+}
+
+impl WriteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Prefer `prefix` for `namespace`, e.g. `C` for
+    /// `urn:ietf:params:xml:ns:caldav`.
+    ///
+    /// Without a preferred prefix, the [`PREFIX`](Element::PREFIX) of the
+    /// element is used. If that prefix is already bound to another namespace
+    /// or the element doesn't have a prefix, a prefix like `ns0` is generated
+    /// instead. Preferred prefixes are never bound to other namespaces.
+    pub fn prefix(
+        mut self,
+        namespace: impl Into<ByteString>,
+        prefix: impl Into<ByteString>,
+    ) -> Self {
+        self.prefixes.insert(namespace.into(), prefix.into());
+        self
+    }
+    /// Declare namespaces on each element that uses them instead of
+    /// declaring all namespaces on the root element.
+    pub fn local_namespaces(mut self, local_namespaces: bool) -> Self {
+        self.local_namespaces = local_namespaces;
+        self
+    }
+    /// The preferred `(namespace, prefix)` pairs set with
+    /// [`prefix`](Self::prefix).
+    pub fn prefixes(&self) -> impl Iterator<Item = (&ByteString, &ByteString)> {
+        self.prefixes.iter()
+    }
+    pub fn is_local_namespaces(&self) -> bool {
+        self.local_namespaces
+    }
+    /// Indent nested elements by `indent_size` times `indent_char`, e.g.
+    /// `b'\t'`. Defaults to two spaces.
+    pub fn indent(mut self, indent_char: u8, indent_size: usize) -> Self {
+        self.indent = Some((indent_char, indent_size));
+        self
+    }
+    /// Write all elements on a single line without indentation.
+    pub fn compact(mut self) -> Self {
+        self.indent = None;
+        self
+    }
+    /// Use `line_ending` between indented elements. Line breaks in text
+    /// content are written as they are.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+    /// Whether to start the document with an XML declaration. Defaults to
+    /// `true`.
+    pub fn declaration(mut self, declaration: bool) -> Self {
+        self.declaration = declaration;
+        self
+    }
+    /// How to write elements without content.
+    pub fn self_closing(mut self, self_closing: SelfClosing) -> Self {
+        self.self_closing = self_closing;
+        self
+    }
+    /// Which characters to escape in text.
+    pub fn escape(mut self, escape: Escape) -> Self {
+        self.escape = escape;
+        self
+    }
+    /// The indentation character and size, or `None` if the output is
+    /// compact.
+    pub fn indentation(&self) -> Option<(u8, usize)> {
+        self.indent
+    }
+    pub fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+    pub fn has_declaration(&self) -> bool {
+        self.declaration
+    }
+    pub fn get_self_closing(&self) -> SelfClosing {
+        self.self_closing
+    }
+    pub fn get_escape(&self) -> Escape {
+        self.escape
+    }
+}
+
+/// Writes [`Value`]s as XML.
+///
+/// Namespaces are either collected in a first pass with
+/// [`resolve_namespaces`](Self::resolve_namespaces) or added by
+/// [`write_element`](Self::write_element) in local mode, and declared on the
+/// next start tag that is written.
+pub(crate) struct XmlWriter<W>
 where
     W: std::io::Write,
 {
     inner: quick_xml::Writer<W>,
-    namespaces: HashMap<ByteString, ByteString>,
+    options: WriteOptions,
+    /// The namespaces in scope and their prefixes
+    namespaces: IndexMap<ByteString, ByteString>,
+    /// The number of `namespaces` that have already been declared
+    declared: usize,
+    /// The current nesting depth, used for indentation
+    depth: usize,
+    /// Whether the next tag starts on a new line
+    line_break: bool,
 }
 
 impl<W> XmlWriter<W>
 where
     W: std::io::Write,
 {
-    fn add_namespace(&mut self, name: &ElementName<ByteString>) {
+    pub(crate) fn new(writer: W, options: &WriteOptions) -> Self {
+        Self {
+            inner: quick_xml::Writer::new(writer),
+            options: options.clone(),
+            namespaces: IndexMap::new(),
+            declared: 0,
+            depth: 0,
+            line_break: false,
+        }
+    }
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        self.inner.get_mut()
+    }
+    pub(crate) fn into_inner(self) -> W {
+        self.inner.into_inner()
+    }
+    pub(crate) fn is_local_namespaces(&self) -> bool {
+        self.options.local_namespaces
+    }
+    /// Write a complete document with `value` as the root element.
+    pub(crate) fn write_document(
+        &mut self,
+        name: &ElementName<ByteString>,
+        value: Value,
+    ) -> Result<(), XmlError> {
+        self.write_decl()?;
+        if !self.options.local_namespaces {
+            self.resolve_namespaces(name, &value);
+        }
+        self.write_element(name, value)
+    }
+    pub(crate) fn write_decl(&mut self) -> Result<(), XmlError> {
+        if self.options.declaration {
+            self.write_event(quick_xml::events::Event::Decl(
+                quick_xml::events::BytesDecl::new("1.0", Some("utf-8"), None),
+            ))?;
+        }
+        Ok(())
+    }
+    /// Write an event, indenting tags like `quick_xml::Writer::new_with_indent`
+    /// but with a configurable line ending.
+    ///
+    /// A text event, even an empty one, keeps the next tag on the same line.
+    fn write_event(&mut self, event: quick_xml::events::Event<'_>) -> Result<(), XmlError> {
+        use quick_xml::events::Event;
+
+        if let Some((indent_char, indent_size)) = self.options.indent {
+            if let Event::End(_) = event {
+                self.depth = self.depth.saturating_sub(1);
+            }
+            if self.line_break && !matches!(event, Event::Text(_) | Event::CData(_)) {
+                let mut line_break = self.options.line_ending.as_bytes().to_vec();
+                line_break.resize(line_break.len() + self.depth * indent_size, indent_char);
+                self.inner
+                    .get_mut()
+                    .write_all(&line_break)
+                    .map_err(quick_xml::Error::from)?;
+            }
+            if let Event::Start(_) = event {
+                self.depth += 1;
+            }
+            self.line_break = !matches!(event, Event::Text(_) | Event::CData(_));
+        }
+        self.inner.write_event(event)?;
+        Ok(())
+    }
+    fn escape<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        match self.options.escape {
+            Escape::Partial => quick_xml::escape::partial_escape(text),
+            Escape::Full => quick_xml::escape::escape(text),
+        }
+    }
+    fn write_empty(&mut self, start: quick_xml::events::BytesStart<'_>) -> Result<(), XmlError> {
+        use quick_xml::events::{BytesStart, BytesText, Event};
+
+        match self.options.self_closing {
+            SelfClosing::Compact => self.write_event(Event::Empty(start)),
+            SelfClosing::Spaced => {
+                let name_len = start.name().as_ref().len();
+                let content = format!("{} ", std::str::from_utf8(&start)?);
+                self.write_event(Event::Empty(BytesStart::from_content(content, name_len)))
+            }
+            SelfClosing::Never => {
+                let end = start.to_end().into_owned();
+                self.write_event(Event::Start(start))?;
+                self.write_event(Event::Text(BytesText::new("")))?;
+                self.write_event(Event::End(end))
+            }
+        }
+    }
+    /// The number of namespaces in scope. Used with
+    /// [`end_scope`](Self::end_scope) to forget the namespaces that were
+    /// declared on an element after it has been closed.
+    pub(crate) fn scope(&self) -> usize {
+        self.namespaces.len()
+    }
+    pub(crate) fn end_scope(&mut self, scope: usize) {
+        self.namespaces.truncate(scope);
+        self.declared = self.declared.min(scope);
+    }
+    pub(crate) fn add_namespace(&mut self, name: &ElementName<ByteString>) {
         if let Some(namespace) = &name.namespace {
-            if !self.namespaces.contains_key(namespace) {
-                self.namespaces.insert(
-                    namespace.clone(),
-                    name.prefix.as_ref().cloned().unwrap_or_else(|| "NS".into()),
-                );
-                // TODO: handle collisions
+            // the `xml` prefix is reserved and must not be declared
+            if namespace == XML_NAMESPACE {
+                return;
             }
+            if self.namespaces.contains_key(namespace) {
+                return;
+            }
+            // prefixes that are preferred for other namespaces are only used
+            // for those namespaces
+            let prefix = self
+                .options
+                .prefixes
+                .get(namespace)
+                .filter(|prefix| self.is_available(prefix))
+                .or(name
+                    .prefix
+                    .as_ref()
+                    .filter(|prefix| self.is_available(prefix) && !self.is_preferred(prefix)))
+                .cloned()
+                .unwrap_or_else(|| {
+                    (0..)
+                        .map(|i| ByteString::from(format!("ns{i}")))
+                        .find(|prefix| self.is_available(prefix) && !self.is_preferred(prefix))
+                        .expect("there are more possible prefixes than namespaces")
+                });
+            self.namespaces.insert(namespace.clone(), prefix);
         }
     }
-    fn resolve_namespaces(&mut self, name: &ElementName<ByteString>, value: &Value) {
+    /// Whether `prefix` can be bound to a new namespace.
+    fn is_available(&self, prefix: &str) -> bool {
+        // prefixes starting with `xml` are reserved
+        !prefix.is_empty()
+            && !prefix
+                .get(..3)
+                .is_some_and(|p| p.eq_ignore_ascii_case("xml"))
+            && !self.namespaces.values().any(|p| p == prefix)
+    }
+    /// Whether `prefix` is preferred for a namespace in the
+    /// [`WriteOptions`].
+    fn is_preferred(&self, prefix: &str) -> bool {
+        self.options.prefixes.values().any(|p| p == prefix)
+    }
+    pub(crate) fn resolve_namespaces(&mut self, name: &ElementName<ByteString>, value: &Value) {
         match value {
             Value::Text(_) | Value::Empty => self.add_namespace(name),
             Value::List(list) => {
@@ -65,6 +389,21 @@ where
                     self.resolve_namespaces(name, value);
                 }
             }
+            Value::Mixed(nodes) => {
+                self.add_namespace(name);
+                for node in nodes {
+                    if let Node::Element(name, value) = node {
+                        self.resolve_namespaces(name, value);
+                    }
+                }
+            }
+            Value::Attributed(attributed) => {
+                let (attributes, value) = &**attributed;
+                for (name, _) in attributes.iter() {
+                    self.add_namespace(name);
+                }
+                self.resolve_namespaces(name, value);
+            }
         }
     }
 }
@@ -75,108 +414,289 @@ where
 {
     fn name<'n>(&self, name: &'n ElementName<ByteString>) -> Cow<'n, str> {
         match &name.namespace {
+            Some(namespace) if namespace == XML_NAMESPACE => {
+                Cow::Owned(format!("{XML_PREFIX}:{}", name.local_name))
+            }
             Some(namespace) => Cow::Owned(format!(
                 "{prefix}:{local_name}",
                 prefix = self
                     .namespaces
                     .get(namespace)
-                    .expect("all namespaces should be resolved in the first pass"),
+                    .expect("namespaces are added before the element is written"),
                 local_name = name.local_name
             )),
             None => Cow::Borrowed(&name.local_name),
         }
     }
-    fn write_toplevel(
+    fn start<'n>(
         &mut self,
-        name: &ElementName<ByteString>,
-        value: Value,
-    ) -> Result<(), XmlError> {
-        use quick_xml::{
-            escape::partial_escape,
-            events::{attributes::Attribute, BytesEnd, BytesStart, BytesText, Event},
-        };
-
-        let raw_name = self.name(name);
-
-        match value {
-            Value::Map(map) => {
-                let mut start = BytesStart::new(&*raw_name);
-                for (namespace, prefix) in &self.namespaces {
-                    start.push_attribute(Attribute::from((
-                        &*format!("xmlns:{prefix}"),
-                        &**namespace,
-                    )));
-                }
+        raw_name: &'n str,
+        attributes: Option<&Attributes>,
+    ) -> quick_xml::events::BytesStart<'n> {
+        use quick_xml::events::{attributes::Attribute, BytesStart};
 
-                self.inner.write_event(Event::Start(start))?;
-                for (tag, value) in map.0 {
-                    self.write_value(&tag, value)?;
-                }
-                self.inner
-                    .write_event(Event::End(BytesEnd::new(raw_name)))?;
-
-                Ok(())
-            }
-            Value::Text(text) => {
-                let mut start = BytesStart::new(&*raw_name);
-                for (namespace, prefix) in &self.namespaces {
-                    start.push_attribute(Attribute::from((
-                        &*format!("xmlns:{prefix}"),
-                        &**namespace,
-                    )));
-                }
-
-                self.inner.write_event(Event::Start(start))?;
-                self.inner
-                    .write_event(Event::Text(BytesText::from_escaped(partial_escape(&text))))?;
-                self.inner
-                    .write_event(Event::End(BytesEnd::new(raw_name)))?;
-
-                Ok(())
-            }
-            _ => unimplemented!(),
+        let mut start = BytesStart::new(raw_name);
+        for (namespace, prefix) in self
+            .namespaces
+            .get_range(self.declared..)
+            .into_iter()
+            .flatten()
+        {
+            start.push_attribute(Attribute::from((&*format!("xmlns:{prefix}"), &**namespace)));
         }
+        self.declared = self.namespaces.len();
+        for (name, value) in attributes.into_iter().flat_map(Attributes::iter) {
+            start.push_attribute(Attribute::from((&*self.name(name), &**value)));
+        }
+        start
     }
-    fn write_value(
+    /// Write the start tag of an element whose content is written
+    /// separately, e.g. the `multistatus` element of a
+    /// [`MultistatusWriter`](crate::MultistatusWriter).
+    pub(crate) fn write_start(&mut self, name: &ElementName<ByteString>) -> Result<(), XmlError> {
+        let raw_name = self.name(name);
+        let start = self.start(&raw_name, None);
+        self.write_event(quick_xml::events::Event::Start(start))
+    }
+    pub(crate) fn write_end(&mut self, name: &ElementName<ByteString>) -> Result<(), XmlError> {
+        let raw_name = self.name(name);
+        self.write_event(quick_xml::events::Event::End(
+            quick_xml::events::BytesEnd::new(raw_name),
+        ))
+    }
+    pub(crate) fn write_element(
         &mut self,
         name: &ElementName<ByteString>,
         value: Value,
     ) -> Result<(), XmlError> {
-        use quick_xml::{
-            escape::partial_escape,
-            events::{BytesEnd, BytesStart, BytesText, Event},
+        use quick_xml::events::{BytesEnd, BytesText, Event};
+
+        let (attributes, value) = match value {
+            Value::Attributed(attributed) => {
+                let (attributes, value) = *attributed;
+                (Some(attributes), value)
+            }
+            value => (None, value),
         };
 
+        let scope = self.scope();
+        if self.options.local_namespaces && !matches!(value, Value::List(_) | Value::Attributed(_))
+        {
+            self.add_namespace(name);
+            for (name, _) in attributes.iter().flat_map(Attributes::iter) {
+                self.add_namespace(name);
+            }
+        }
         let raw_name = self.name(name);
 
         match value {
             Value::Empty => {
-                self.inner
-                    .write_event(Event::Empty(BytesStart::new(raw_name)))?;
+                let start = self.start(&raw_name, attributes.as_ref());
+                self.write_empty(start)?;
             }
             Value::Text(text) => {
-                self.inner
-                    .write_event(Event::Start(BytesStart::new(&*raw_name)))?;
-                self.inner
-                    .write_event(Event::Text(BytesText::from_escaped(partial_escape(&text))))?;
-                self.inner
-                    .write_event(Event::End(BytesEnd::new(raw_name)))?;
+                let start = self.start(&raw_name, attributes.as_ref());
+                self.write_event(Event::Start(start))?;
+                let text = self.escape(&text);
+                self.write_event(Event::Text(BytesText::from_escaped(text)))?;
+                self.write_event(Event::End(BytesEnd::new(raw_name)))?;
             }
             Value::List(list) => {
                 for value in *list {
-                    self.write_value(name, value)?;
+                    self.write_element(name, value)?;
                 }
             }
             Value::Map(map) => {
-                self.inner
-                    .write_event(Event::Start(BytesStart::new(&*raw_name)))?;
+                let start = self.start(&raw_name, attributes.as_ref());
+                self.write_event(Event::Start(start))?;
                 for (tag, value) in map.0 {
-                    self.write_value(&tag, value)?;
+                    self.write_element(&tag, value)?;
                 }
-                self.inner
-                    .write_event(Event::End(BytesEnd::new(raw_name)))?;
+                self.write_event(Event::End(BytesEnd::new(raw_name)))?;
+            }
+            Value::Mixed(nodes) => {
+                let start = self.start(&raw_name, attributes.as_ref());
+                self.write_event(Event::Start(start))?;
+                for node in nodes {
+                    match node {
+                        Node::Text(text) => {
+                            let text = self.escape(&text);
+                            self.write_event(Event::Text(BytesText::from_escaped(text)))?
+                        }
+                        Node::Element(tag, value) => {
+                            // an empty text event keeps the writer from
+                            // indenting, which would alter the content
+                            self.write_event(Event::Text(BytesText::new("")))?;
+                            self.write_element(&tag, value)?;
+                        }
+                    }
+                }
+                self.write_event(Event::Text(BytesText::new("")))?;
+                self.write_event(Event::End(BytesEnd::new(raw_name)))?;
+            }
+            Value::Attributed(attributed) => {
+                // not constructed by `Value::with_attributes()`, the inner
+                // attributes take precedence
+                let (mut inner_attributes, value) = *attributed;
+                for (name, value) in attributes.into_iter().flat_map(|a| a.0) {
+                    inner_attributes.0.entry(name).or_insert(value);
+                }
+                self.write_element(name, value.with_attributes(inner_attributes))?;
             }
         }
+        self.end_scope(scope);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        elements::Properties, properties::DisplayName, Element, FromXml, IntoXml, WriteOptions,
+    };
+
+    #[derive(Element)]
+    #[xml(namespace = "urn:example:a", prefix = "x")]
+    struct A;
+
+    #[derive(Element)]
+    #[xml(namespace = "urn:example:b", prefix = "x")]
+    struct B;
+
+    fn properties() -> Properties {
+        Properties::new()
+            .with(DisplayName("Example".into()))
+            .with_name::<A>()
+            .with_name::<B>()
+    }
+
+    fn write(properties: Properties, options: &WriteOptions) -> eyre::Result<String> {
+        Ok(String::from_utf8(
+            properties.into_xml_with(options)?.to_vec(),
+        )?)
+    }
+
+    #[test]
+    fn collisions() -> eyre::Result<()> {
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:" xmlns:x="urn:example:a" xmlns:ns0="urn:example:b">
+  <d:displayname>Example</d:displayname>
+  <x:a/>
+  <ns0:b/>
+</d:prop>
+"#;
+        assert_eq!(write(properties(), &WriteOptions::new())?, expected.trim());
+
+        // elements that were read from XML don't have a prefix
+        let properties = Properties::from_xml(
+            r#"<prop xmlns="DAV:"><a xmlns="urn:example:a"/><b xmlns="urn:example:b"/></prop>"#,
+        )?;
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:" xmlns:ns0="urn:example:a" xmlns:ns1="urn:example:b">
+  <ns0:a/>
+  <ns1:b/>
+</d:prop>
+"#;
+        assert_eq!(write(properties, &WriteOptions::new())?, expected.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    fn registry() -> eyre::Result<()> {
+        let options = WriteOptions::new()
+            .prefix("DAV:", "D")
+            .prefix("urn:example:b", "b")
+            // reserved prefixes are ignored
+            .prefix("urn:example:a", "xmlfoo");
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<D:prop xmlns:D="DAV:" xmlns:x="urn:example:a" xmlns:b="urn:example:b">
+  <D:displayname>Example</D:displayname>
+  <x:a/>
+  <b:b/>
+</D:prop>
+"#;
+        assert_eq!(write(properties(), &options)?, expected.trim());
+
+        // a preferred prefix isn't used for another namespace, even if that
+        // namespace appears first
+        let options = WriteOptions::new().prefix("urn:example:b", "x");
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:" xmlns:ns0="urn:example:a" xmlns:x="urn:example:b">
+  <d:displayname>Example</d:displayname>
+  <ns0:a/>
+  <x:b/>
+</d:prop>
+"#;
+        let xml = write(properties(), &options)?;
+        assert_eq!(xml, expected.trim());
+        assert_eq!(Properties::from_xml(xml)?, properties());
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_namespaces() -> eyre::Result<()> {
+        let options = WriteOptions::new().local_namespaces(true);
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:">
+  <d:displayname>Example</d:displayname>
+  <x:a xmlns:x="urn:example:a"/>
+  <x:b xmlns:x="urn:example:b"/>
+</d:prop>
+"#;
+        let xml = write(properties(), &options)?;
+        assert_eq!(xml, expected.trim());
+        assert_eq!(Properties::from_xml(xml)?, properties());
+
+        Ok(())
+    }
+
+    #[test]
+    fn formatting() -> eyre::Result<()> {
+        use crate::{Escape, LineEnding, SelfClosing};
+
+        let properties = || properties().with(DisplayName(r#"<"Example">"#.into()));
+
+        let options = WriteOptions::new().compact().declaration(false);
+        let expected = r#"<d:prop xmlns:d="DAV:" xmlns:x="urn:example:a" xmlns:ns0="urn:example:b"><d:displayname>Example</d:displayname><d:displayname>&lt;"Example"&gt;</d:displayname><x:a/><ns0:b/></d:prop>"#;
+        assert_eq!(write(properties(), &options)?, expected);
+
+        let options = WriteOptions::new()
+            .indent(b'\t', 1)
+            .line_ending(LineEnding::CrLf)
+            .self_closing(SelfClosing::Spaced)
+            .escape(Escape::Full);
+        let expected = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\r\n\
+            <d:prop xmlns:d=\"DAV:\" xmlns:x=\"urn:example:a\" xmlns:ns0=\"urn:example:b\">\r\n\
+            \t<d:displayname>Example</d:displayname>\r\n\
+            \t<d:displayname>&lt;&quot;Example&quot;&gt;</d:displayname>\r\n\
+            \t<x:a />\r\n\
+            \t<ns0:b />\r\n\
+            </d:prop>";
+        assert_eq!(write(properties(), &options)?, expected);
+
+        let options = WriteOptions::new().self_closing(SelfClosing::Never);
+        let expected = r#"
+<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:" xmlns:x="urn:example:a" xmlns:ns0="urn:example:b">
+  <d:displayname>Example</d:displayname>
+  <d:displayname>&lt;"Example"&gt;</d:displayname>
+  <x:a></x:a>
+  <ns0:b></ns0:b>
+</d:prop>
+"#;
+        let xml = write(properties(), &options)?;
+        assert_eq!(xml, expected.trim());
+        assert_eq!(Properties::from_xml(xml)?, properties());
 
         Ok(())
     }